@@ -5,8 +5,8 @@ use bevy::prelude::*;
 use bevy_discord::DiscordBotPlugin;
 use bevy_discord::config::DiscordBotConfig;
 use bevy_discord::messages::bot::*;
+use bevy_discord::messages::http::DiscordRequest;
 use bevy_discord::serenity::all::*;
-use serde_json::json;
 
 fn main() {
     let config = DiscordBotConfig::default()
@@ -27,59 +27,37 @@ fn main() {
 
 fn handle_discord_messages(
     mut messages: MessageReader<DiscordMessage>,
-    http: Option<Res<bevy_discord::res::DiscordHttpResource>>,
+    mut requests: MessageWriter<DiscordRequest>,
 ) {
     for message in messages.read() {
-        if let Some(http) = &http {
-            if message.new_message.author.bot {
-                continue;
-            }
-
-            if message.new_message.content == "!react" {
-                let http = http.client();
-                let message_id = message.new_message.id;
-                let channel_id = message.new_message.channel_id;
+        if message.new_message.author.bot {
+            continue;
+        }
 
-                bevy_discord::runtime::tokio_runtime().spawn(async move {
-                    // Add a thumbs up reaction
-                    let _ = http
-                        .create_reaction(
-                            channel_id,
-                            message_id,
-                            &ReactionType::Unicode("👍".to_string()),
-                        )
-                        .await;
-                });
-            }
+        if message.new_message.content == "!react" {
+            // Add a thumbs up reaction
+            requests.write(DiscordRequest::AddReaction {
+                channel_id: message.new_message.channel_id,
+                message_id: message.new_message.id,
+                reaction: ReactionType::Unicode("👍".to_string()),
+            });
         }
     }
 }
 
 fn handle_discord_reactions(
     mut reaction_add: MessageReader<ReactionAddMessage>,
-    http: Option<Res<bevy_discord::res::DiscordHttpResource>>,
+    mut requests: MessageWriter<DiscordRequest>,
 ) {
     for reaction in reaction_add.read() {
-        if let Some(http) = &http {
-            // Skip bot reactions
-            if reaction.add_reaction.user_id == Some(reaction.ctx.cache.current_user().id) {
-                continue;
-            }
-
-            let http = http.client();
-            let channel_id = reaction.add_reaction.channel_id;
-
-            bevy_discord::runtime::tokio_runtime().spawn(async move {
-                let _ = http
-                    .send_message(
-                        channel_id,
-                        vec![],
-                        &json!({
-                            "content": "Thanks for reacting! 😊"
-                        }),
-                    )
-                    .await;
-            });
+        // Skip bot reactions
+        if reaction.add_reaction.user_id == Some(reaction.ctx.cache.current_user().id) {
+            continue;
         }
+
+        requests.write(DiscordRequest::SendMessage {
+            channel_id: reaction.add_reaction.channel_id,
+            message: CreateMessage::new().content("Thanks for reacting! 😊"),
+        });
     }
 }
@@ -5,12 +5,11 @@ use bevy::prelude::*;
 use bevy_discord::DiscordBotPlugin;
 use bevy_discord::config::DiscordBotConfig;
 use bevy_discord::messages::bot::*;
-use bevy_discord::runtime::tokio_runtime;
+use bevy_discord::messages::http::DiscordRequest;
 use bevy_discord::serenity::all::{
-    Command, CreateCommand, CreateInteractionResponse, CreateInteractionResponseMessage,
-    GatewayIntents,
+    CreateCommand, CreateInteractionResponse, CreateInteractionResponseMessage, GatewayIntents,
 };
-use bevy_discord::serenity::model::application::Interaction;
+use bevy_discord::slash_commands::{CommandInvoked, DiscordCommandAppExt};
 
 fn main() {
     let config = DiscordBotConfig::default()
@@ -20,47 +19,27 @@ fn main() {
     App::new()
         .add_plugins(MinimalPlugins)
         .add_plugins(DiscordBotPlugin::new(config))
-        .add_systems(Update, (handle_ready, handle_interactions))
+        // Registered once the bot is ready, unless it already exists
+        .add_discord_command(CreateCommand::new("ping").description("A simple ping command"))
+        .add_discord_command_system("ping", ping)
+        .add_systems(Update, handle_sync)
         .run();
 }
 
-fn handle_ready(mut ready_events: MessageReader<BotReadyMessage>) {
-    for event in ready_events.read() {
-        let http = event.ctx.http.clone();
-
-        // Register global slash command
-        tokio_runtime().spawn(async move {
-            let command = Command::create_global_command(
-                &http,
-                CreateCommand::new("ping").description("A simple ping command"),
-            )
-            .await;
-
-            if let Err(why) = command {
-                println!("Error creating command: {:?}", why);
-            }
-        });
+fn handle_sync(mut sync_events: MessageReader<CommandSyncMessage>) {
+    for event in sync_events.read() {
+        if let Err(why) = &event.result {
+            println!("Error syncing commands: {}", why);
+        }
     }
 }
 
-fn handle_interactions(mut interaction_events: MessageReader<InteractionCreateMessage>) {
-    for event in interaction_events.read() {
-        if let Interaction::Command(command) = &event.interaction {
-            if command.data.name.as_str() == "ping" {
-                let http = event.ctx.http.clone();
-                let command = command.clone();
-
-                tokio_runtime().spawn(async move {
-                    let _ = command
-                        .create_response(
-                            &http,
-                            CreateInteractionResponse::Message(
-                                CreateInteractionResponseMessage::new().content("Pong! 🏓"),
-                            ),
-                        )
-                        .await;
-                });
-            }
-        }
-    }
+fn ping(In(invoked): In<CommandInvoked>, mut requests: MessageWriter<DiscordRequest>) {
+    requests.write(DiscordRequest::RespondInteraction {
+        interaction_id: invoked.interaction.id,
+        token: invoked.interaction.token.clone(),
+        response: CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().content("Pong! 🏓"),
+        ),
+    });
 }
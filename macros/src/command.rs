@@ -0,0 +1,220 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, Fields, Lit, LitStr, Token};
+
+use crate::{option_inner, snake_case};
+
+/// `#[command(...)]` on the struct.
+#[derive(Default)]
+struct CommandAttributes {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+}
+
+/// `#[option(...)]` on a field.
+#[derive(Default)]
+struct OptionAttributes {
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    min_length: Option<Lit>,
+    max_length: Option<Lit>,
+    choices: Vec<(LitStr, Lit)>,
+    autocomplete: bool,
+}
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "DiscordCommand can only be derived for structs",
+        ));
+    };
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "DiscordCommand can't be derived for tuple structs",
+            ));
+        }
+    };
+
+    let mut attributes = CommandAttributes::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("command"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                attributes.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("description") {
+                attributes.description = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `name` or `description`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let name = attributes
+        .name
+        .map(|name| name.value())
+        .unwrap_or_else(|| snake_case(&ident.to_string()));
+    let Some(description) = attributes.description else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "expected a `#[command(description = \"...\")]` attribute, discord requires one",
+        ));
+    };
+
+    // Discord rejects required options that come after optional ones
+    let mut required_options = Vec::new();
+    let mut optional_options = Vec::new();
+    let mut parsers = Vec::new();
+
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("named fields have an ident");
+
+        let mut attributes = OptionAttributes::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("option"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attributes.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("description") {
+                    attributes.description = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("min") {
+                    attributes.min = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max") {
+                    attributes.max = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("min_length") {
+                    attributes.min_length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max_length") {
+                    attributes.max_length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("autocomplete") {
+                    attributes.autocomplete = true;
+                } else if meta.path.is_ident("choice") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let name: LitStr = content.parse()?;
+                    content.parse::<Token![,]>()?;
+                    let value: Lit = content.parse()?;
+                    attributes.choices.push((name, value));
+                } else {
+                    return Err(meta.error(
+                        "expected `name`, `description`, `min`, `max`, `min_length`, \
+                        `max_length`, `autocomplete` or `choice`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        let option_name = attributes
+            .name
+            .map(|name| name.value())
+            .unwrap_or_else(|| field_ident.to_string().trim_start_matches("r#").to_string());
+        let Some(option_description) = attributes.description else {
+            return Err(syn::Error::new_spanned(
+                field_ident,
+                "expected an `#[option(description = \"...\")]` attribute, discord requires one",
+            ));
+        };
+
+        let autocomplete = attributes.autocomplete;
+        let (ty, required) = match option_inner(&field.ty) {
+            Some(inner) => (inner, false),
+            None => (&field.ty, true),
+        };
+
+        let min = attributes.min.map(|min| {
+            quote! { let option = <#ty as ::bevy_discord::slash_commands::__private::NumericOption>::min_value(option, #min); }
+        });
+        let max = attributes.max.map(|max| {
+            quote! { let option = <#ty as ::bevy_discord::slash_commands::__private::NumericOption>::max_value(option, #max); }
+        });
+        let min_length = attributes
+            .min_length
+            .map(|min_length| quote! { let option = option.min_length(#min_length); });
+        let max_length = attributes
+            .max_length
+            .map(|max_length| quote! { let option = option.max_length(#max_length); });
+        let choices = attributes
+            .choices
+            .iter()
+            .map(|(name, value)| match value {
+                Lit::Str(_) => Ok(quote! { let option = option.add_string_choice(#name, #value); }),
+                Lit::Int(_) => Ok(quote! { let option = option.add_int_choice(#name, #value); }),
+                Lit::Float(_) => {
+                    Ok(quote! { let option = option.add_number_choice(#name, #value); })
+                }
+                _ => Err(syn::Error::new_spanned(
+                    value,
+                    "expected a string, integer or float choice",
+                )),
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let options = if required {
+            &mut required_options
+        } else {
+            &mut optional_options
+        };
+        options.push(quote! {
+            .add_option({
+                let option = ::bevy_discord::serenity::all::CreateCommandOption::new(
+                    <#ty as ::bevy_discord::slash_commands::DiscordOptionValue>::KIND,
+                    #option_name,
+                    #option_description,
+                )
+                .required(#required)
+                .set_autocomplete(#autocomplete);
+                #min
+                #max
+                #min_length
+                #max_length
+                #(#choices)*
+                option
+            })
+        });
+
+        parsers.push(if required {
+            quote! { #field_ident: ::bevy_discord::slash_commands::__private::required(options, #option_name)? }
+        } else {
+            quote! { #field_ident: ::bevy_discord::slash_commands::__private::optional(options, #option_name)? }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_discord::slash_commands::DiscordCommand for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+
+            fn create_command() -> ::bevy_discord::serenity::all::CreateCommand {
+                ::bevy_discord::serenity::all::CreateCommand::new(#name)
+                    .description(#description)
+                    #(#required_options)*
+                    #(#optional_options)*
+            }
+
+            fn from_options(
+                options: &[::bevy_discord::serenity::all::CommandDataOption],
+            ) -> ::core::result::Result<Self, ::bevy_discord::slash_commands::CommandParseError> {
+                ::core::result::Result::Ok(Self {
+                    #(#parsers,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,66 @@
+//! Derive macros for `bevy-discord`, use them through the re-exports of `bevy-discord`.
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+mod command;
+mod modal;
+
+/// Implements `DiscordCommand`, see `bevy_discord::slash_commands::DiscordCommand`.
+#[proc_macro_derive(DiscordCommand, attributes(command, option))]
+pub fn derive_discord_command(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    command::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `DiscordModal`, see `bevy_discord::modals::DiscordModal`.
+#[proc_macro_derive(DiscordModal, attributes(modal, input))]
+pub fn derive_discord_modal(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    modal::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Converts `CamelCase` to `snake_case`, which is how discord names its commands and options.
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+
+    for (i, char) in name.chars().enumerate() {
+        if char.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
+            }
+            snake.extend(char.to_lowercase());
+        } else {
+            snake.push(char);
+        }
+    }
+
+    snake
+}
+
+/// Returns the `T` of `Option<T>`.
+pub(crate) fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
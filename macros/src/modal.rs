@@ -0,0 +1,186 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Lit, LitStr};
+
+use crate::{option_inner, snake_case};
+
+/// `#[modal(...)]` on the struct.
+#[derive(Default)]
+struct ModalAttributes {
+    custom_id: Option<LitStr>,
+    title: Option<LitStr>,
+}
+
+/// `#[input(...)]` on a field.
+#[derive(Default)]
+struct InputAttributes {
+    custom_id: Option<LitStr>,
+    label: Option<LitStr>,
+    placeholder: Option<LitStr>,
+    value: Option<LitStr>,
+    min_length: Option<Lit>,
+    max_length: Option<Lit>,
+    paragraph: bool,
+}
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "DiscordModal can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "DiscordModal can only be derived for structs with named fields",
+        ));
+    };
+
+    // Each input takes a row, and a modal has at most 5 of them
+    if let Some(field) = fields.named.iter().nth(5) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "a modal can't have more than 5 inputs",
+        ));
+    }
+
+    let mut attributes = ModalAttributes::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("modal"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("custom_id") {
+                attributes.custom_id = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("title") {
+                attributes.title = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("expected `custom_id` or `title`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let custom_id = attributes
+        .custom_id
+        .map(|custom_id| custom_id.value())
+        .unwrap_or_else(|| snake_case(&ident.to_string()));
+    let title = attributes
+        .title
+        .map(|title| title.value())
+        .unwrap_or_else(|| ident.to_string());
+
+    let mut inputs = Vec::new();
+    let mut parsers = Vec::new();
+
+    for field in &fields.named {
+        let field_ident = field.ident.as_ref().expect("named fields have an ident");
+
+        let mut attributes = InputAttributes::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("input"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("custom_id") {
+                    attributes.custom_id = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("label") {
+                    attributes.label = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("placeholder") {
+                    attributes.placeholder = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("value") {
+                    attributes.value = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("min_length") {
+                    attributes.min_length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max_length") {
+                    attributes.max_length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("paragraph") {
+                    attributes.paragraph = true;
+                } else {
+                    return Err(meta.error(
+                        "expected `custom_id`, `label`, `placeholder`, `value`, `min_length`, \
+                        `max_length` or `paragraph`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        let input_id = attributes
+            .custom_id
+            .map(|custom_id| custom_id.value())
+            .unwrap_or_else(|| field_ident.to_string().trim_start_matches("r#").to_string());
+        let label = attributes
+            .label
+            .map(|label| label.value())
+            .unwrap_or_else(|| input_id.clone());
+        let required = option_inner(&field.ty).is_none();
+        let style = if attributes.paragraph {
+            quote! { ::bevy_discord::serenity::all::InputTextStyle::Paragraph }
+        } else {
+            quote! { ::bevy_discord::serenity::all::InputTextStyle::Short }
+        };
+
+        let placeholder = attributes
+            .placeholder
+            .map(|placeholder| quote! { let input = input.placeholder(#placeholder); });
+        let value = attributes
+            .value
+            .map(|value| quote! { let input = input.value(#value); });
+        let min_length = attributes
+            .min_length
+            .map(|min_length| quote! { let input = input.min_length(#min_length); });
+        let max_length = attributes
+            .max_length
+            .map(|max_length| quote! { let input = input.max_length(#max_length); });
+
+        inputs.push(quote! {
+            ::bevy_discord::serenity::all::CreateActionRow::InputText({
+                let input = ::bevy_discord::serenity::all::CreateInputText::new(
+                    #style,
+                    #label,
+                    #input_id,
+                )
+                .required(#required);
+                #placeholder
+                #value
+                #min_length
+                #max_length
+                input
+            })
+        });
+
+        parsers.push(if required {
+            quote! { #field_ident: ::bevy_discord::modals::__private::required(components, #input_id)? }
+        } else {
+            quote! { #field_ident: ::bevy_discord::modals::__private::optional(components, #input_id) }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_discord::modals::DiscordModal for #ident #ty_generics #where_clause {
+            const CUSTOM_ID: &'static str = #custom_id;
+
+            fn create_modal() -> ::bevy_discord::serenity::all::CreateModal {
+                ::bevy_discord::serenity::all::CreateModal::new(#custom_id, #title)
+                    .components(::std::vec![#(#inputs),*])
+            }
+
+            fn from_components(
+                components: &[::bevy_discord::serenity::all::ActionRow],
+            ) -> ::core::result::Result<Self, ::bevy_discord::modals::ModalParseError> {
+                ::core::result::Result::Ok(Self {
+                    #(#parsers,)*
+                })
+            }
+        }
+    })
+}
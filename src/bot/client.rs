@@ -0,0 +1,409 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bevy_app::AppExit;
+use bevy_ecs::prelude::*;
+use bevy_state::state::State;
+use serenity::all::{ClientBuilder, ShardManager};
+use tokio::sync::{oneshot, watch};
+use tracing::{error, info, warn};
+
+use crate::bot::handle::{EventSender, Handle};
+use crate::bot::watchdog::WatchedInteractions;
+use crate::channel::ChannelSender;
+use crate::config::DiscordBotConfig;
+use crate::error::DiscordBotError;
+use crate::http::discord_http;
+use crate::messages::bot::{
+    BotDisconnectedMessage, BotRestartMessage, DiscordBotErrorMessage, SetBotPresenceMessage,
+};
+use crate::messages::{ContextReaders, MessageCollectionBot};
+use crate::runtime::{tokio_runtime, tracked_finished};
+use crate::state::DiscordConnectionState;
+
+/// Used when [`DiscordBotConfig::shutdown_timeout`] isn't set.
+pub(crate) const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keeps hold of the running discord client, so that it can be controlled from bevy.
+#[derive(Resource)]
+pub(crate) struct BotClient {
+    /// Set by the client task while a client is running.
+    pub(crate) shard_manager: Arc<Mutex<Option<Arc<ShardManager>>>>,
+    /// Copy of [`DiscordBotConfig`] used by the supervisor to rebuild the client.
+    pub(crate) config: Arc<Mutex<DiscordBotConfig>>,
+    /// Set to `true` once the app is exiting, so that the supervisor stops the client and
+    /// doesn't restart it.
+    pub(crate) shutdown: watch::Sender<bool>,
+    /// Receives whether the shutdown finished in time, once it has started and until it has
+    /// been waited for.
+    shutdown_result: Option<oneshot::Receiver<bool>>,
+    /// Interactions watched by the client, kept when it restarts.
+    pub(crate) watched: WatchedInteractions,
+    /// Presence updates waiting for the client to be built.
+    pending_presence: Vec<SetBotPresenceMessage>,
+}
+
+impl BotClient {
+    pub(crate) fn new(config: DiscordBotConfig) -> Self {
+        Self {
+            shard_manager: Arc::default(),
+            config: Arc::new(Mutex::new(config)),
+            shutdown: watch::Sender::default(),
+            shutdown_result: None,
+            watched: WatchedInteractions::default(),
+            pending_presence: Vec::new(),
+        }
+    }
+
+    pub(crate) fn shard_manager(&self) -> Option<Arc<ShardManager>> {
+        self.shard_manager
+            .lock()
+            .expect("shard manager lock shouldn't be poisoned")
+            .clone()
+    }
+}
+
+/// Runs the discord client, and restarts it according to [`DiscordBotConfig::restart_policy`]
+/// whenever it stops.
+pub(crate) async fn supervise_client(
+    tx: ChannelSender<MessageCollectionBot>,
+    config: Arc<Mutex<DiscordBotConfig>>,
+    shard_manager: Arc<Mutex<Option<Arc<ShardManager>>>>,
+    shutdown: watch::Sender<bool>,
+    watched: WatchedInteractions,
+    readers: ContextReaders,
+) {
+    let mut attempt = 0;
+    #[allow(unused_mut)]
+    let mut events = EventSender::new(
+        tx.clone(),
+        &config.lock().expect("config lock shouldn't be poisoned"),
+        readers,
+    );
+    #[cfg(feature = "recording")]
+    {
+        events.recorder = recorder(&config);
+    }
+
+    loop {
+        let config = config
+            .lock()
+            .expect("config lock shouldn't be poisoned")
+            .clone();
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let result = run_client(
+            &config,
+            &events,
+            &shard_manager,
+            &shutdown,
+            &ready,
+            &watched,
+        )
+        .await;
+
+        *shard_manager
+            .lock()
+            .expect("shard manager lock shouldn't be poisoned") = None;
+
+        if *shutdown.borrow() {
+            return;
+        }
+
+        let error = match result {
+            Ok(()) => {
+                send(
+                    &tx,
+                    MessageCollectionBot::BotDisconnectedMessage(BotDisconnectedMessage {
+                        graceful: true,
+                    }),
+                )
+                .await;
+                None
+            }
+            Err(err) => {
+                let error = DiscordBotError::from(err);
+                error!("Discord client stopped: {}", error);
+                send(
+                    &tx,
+                    MessageCollectionBot::DiscordBotErrorMessage(DiscordBotErrorMessage {
+                        error: error.clone(),
+                    }),
+                )
+                .await;
+                Some(error)
+            }
+        };
+
+        let Some(restart_policy) = config.restart_policy else {
+            return;
+        };
+
+        if let Some(error) = error.filter(|error| !error.is_recoverable()) {
+            error!("Not restarting the discord client: {}", error);
+            return;
+        }
+
+        // Only consecutive failures count towards `max_attempts`
+        if ready.load(Ordering::SeqCst) {
+            attempt = 0;
+        }
+        attempt += 1;
+
+        if restart_policy
+            .max_attempts
+            .is_some_and(|max_attempts| attempt > max_attempts)
+        {
+            error!(
+                "Giving up on restarting the discord client after {} attempts",
+                attempt - 1
+            );
+            return;
+        }
+
+        let delay = restart_policy.delay(attempt);
+        info!(
+            "Restarting the discord client in {:?} (attempt {})",
+            delay, attempt
+        );
+        send(
+            &tx,
+            MessageCollectionBot::BotRestartMessage(BotRestartMessage { attempt, delay }),
+        )
+        .await;
+
+        // The app may exit during the delay
+        let mut exiting = shutdown.subscribe();
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = exiting.wait_for(|shutdown| *shutdown) => return,
+        }
+    }
+}
+
+/// Creates the file the events are recorded to, which is kept when the client restarts.
+#[cfg(feature = "recording")]
+fn recorder(config: &Mutex<DiscordBotConfig>) -> Option<Arc<crate::recording::EventRecorder>> {
+    let config = config.lock().expect("config lock shouldn't be poisoned");
+    let path = config.record_events.as_ref()?;
+
+    match crate::recording::EventRecorder::create(path) {
+        Ok(recorder) => Some(Arc::new(recorder)),
+        Err(err) => {
+            error!("Unable to record the events to {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+/// Builds the client from the config and runs it until it stops.
+async fn run_client(
+    config: &DiscordBotConfig,
+    tx: &EventSender,
+    shard_manager: &Mutex<Option<Arc<ShardManager>>>,
+    shutdown: &watch::Sender<bool>,
+    ready: &Arc<AtomicBool>,
+    watched: &WatchedInteractions,
+) -> serenity::Result<()> {
+    let http = discord_http(&config.token, config.base_url.as_deref());
+    let mut client_builder = ClientBuilder::new_with_http(http, config.gateway_intents)
+        .event_handler(Handle {
+            tx: tx.clone(),
+            ready: ready.clone(),
+            interaction_watchdog: config.interaction_watchdog.clone(),
+            watched: watched.clone(),
+        });
+
+    if let Some(status) = config.status {
+        client_builder = client_builder.status(status);
+    }
+
+    if let Some(activity) = config.activity.clone() {
+        client_builder = client_builder.activity(activity);
+    }
+
+    let mut client = client_builder.await?;
+
+    *shard_manager
+        .lock()
+        .expect("shard manager lock shouldn't be poisoned") = Some(client.shard_manager.clone());
+
+    // The app may have exited while the client was being built, in which case `shutdown_bot`
+    // didn't find a shard manager to close
+    if *shutdown.borrow() {
+        return Ok(());
+    }
+
+    // Closes the shards if the app exits before they are running, since closing the shard
+    // manager doesn't do anything until then
+    let mut exiting = shutdown.subscribe();
+    let manager = client.shard_manager.clone();
+    let closer = tokio::spawn(async move {
+        if exiting.wait_for(|shutdown| *shutdown).await.is_err() {
+            return;
+        }
+
+        while manager.runners.lock().await.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        manager.shutdown_all().await;
+    });
+
+    let result = if config.shards == 0 {
+        client.start().await
+    } else {
+        client.start_shards(config.shards).await
+    };
+
+    closer.abort();
+    result
+}
+
+async fn send(tx: &ChannelSender<MessageCollectionBot>, message: MessageCollectionBot) {
+    if tx.send_async(message).await.is_err() {
+        error!("Unable to send event to the channel")
+    }
+}
+
+/// Keeps the supervisor's copy of [`DiscordBotConfig`] up to date.
+pub(crate) fn sync_bot_config(
+    discord_bot_config: Res<DiscordBotConfig>,
+    bot_client: Res<BotClient>,
+) {
+    if discord_bot_config.is_changed() {
+        *bot_client
+            .config
+            .lock()
+            .expect("config lock shouldn't be poisoned") = discord_bot_config.clone();
+    }
+}
+
+/// Shuts down the discord client once the app is exiting, and writes a
+/// [`BotDisconnectedMessage`] once it is done.
+///
+/// It blocks the frame the app exits in, for at most [`DiscordBotConfig::shutdown_timeout`]. An
+/// `AppExit` written after this system, e.g. in [`Last`](bevy_app::Last), is handled when
+/// [`BotClient`] is dropped instead.
+pub(crate) fn shutdown_bot(
+    mut app_exit: MessageReader<AppExit>,
+    mut bot_client: ResMut<BotClient>,
+    mut disconnected: MessageWriter<BotDisconnectedMessage>,
+) {
+    if app_exit.read().last().is_none() {
+        return;
+    }
+
+    bot_client.start_shutdown();
+
+    if let Some(graceful) = bot_client.wait_for_shutdown() {
+        disconnected.write(BotDisconnectedMessage { graceful });
+    }
+}
+
+impl BotClient {
+    /// Closes all the shards and waits for the pending outbound work in the background, bounded
+    /// by [`DiscordBotConfig::shutdown_timeout`].
+    fn start_shutdown(&mut self) {
+        if self.shutdown.send_replace(true) {
+            return;
+        }
+
+        let shard_manager = self.shard_manager();
+        let timeout = self
+            .config
+            .lock()
+            .expect("config lock shouldn't be poisoned")
+            .shutdown_timeout
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+        let (done, result) = oneshot::channel();
+
+        info!("Shutting down the discord client");
+
+        tokio_runtime().spawn(async move {
+            let shutdown = tokio::time::timeout(timeout, async move {
+                if let Some(shard_manager) = shard_manager {
+                    shard_manager.shutdown_all().await;
+                }
+
+                tracked_finished().await;
+            });
+
+            let graceful = shutdown.await.is_ok();
+            if !graceful {
+                warn!(
+                    "Discord client didn't shut down within {:?}, remaining work is dropped",
+                    timeout
+                );
+            }
+
+            let _ = done.send(graceful);
+        });
+        self.shutdown_result = Some(result);
+    }
+
+    /// Waits for the shutdown started by [`start_shutdown`](Self::start_shutdown), and returns
+    /// whether it finished in time. Returns `None` if it isn't started or was already waited for,
+    /// or from within an async runtime, e.g. if the app is run or dropped by a task.
+    fn wait_for_shutdown(&mut self) -> Option<bool> {
+        // `blocking_recv` panics from within an async runtime
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return None;
+        }
+
+        self.shutdown_result.take()?.blocking_recv().ok()
+    }
+}
+
+impl Drop for BotClient {
+    /// Waits for the shutdown once the app is dropped, after its last frame.
+    fn drop(&mut self) {
+        self.start_shutdown();
+        self.wait_for_shutdown();
+    }
+}
+
+/// Applies [`SetBotPresenceMessage`] through the shard manager.
+pub(crate) fn apply_bot_presence(
+    mut presence_updates: MessageReader<SetBotPresenceMessage>,
+    mut bot_client: ResMut<BotClient>,
+    mut discord_bot_config: ResMut<DiscordBotConfig>,
+    connection_state: Res<State<DiscordConnectionState>>,
+) {
+    for presence in presence_updates.read() {
+        // Keep the config up to date, so that a restarted client starts with the latest presence
+        if presence.shard.is_none() {
+            discord_bot_config.status = Some(presence.status);
+            discord_bot_config.activity = presence.activity.clone();
+        }
+
+        bot_client.pending_presence.push(presence.clone());
+    }
+
+    // The shard runners only exist once the bot is connected
+    if bot_client.pending_presence.is_empty()
+        || *connection_state.get() != DiscordConnectionState::Ready
+    {
+        return;
+    }
+
+    let Some(shard_manager) = bot_client.shard_manager() else {
+        return;
+    };
+
+    let pending_presence = std::mem::take(&mut bot_client.pending_presence);
+
+    tokio_runtime().spawn(async move {
+        let runners = shard_manager.runners.lock().await;
+
+        for presence in pending_presence {
+            for (shard_id, runner) in runners.iter() {
+                if presence.shard.is_none_or(|shard| shard == *shard_id) {
+                    runner
+                        .runner_tx
+                        .set_presence(presence.activity.clone(), presence.status);
+                }
+            }
+        }
+    });
+}
@@ -1,15 +1,66 @@
 use bevy_ecs::prelude::*;
+use bevy_state::state::NextState;
+use serenity::all::ConnectionStage;
 
+use crate::bot::client::BotClient;
+use crate::http::request::CompletedRequests;
 use crate::messages::bot::*;
+use crate::messages::payload::ResumeMessagePayload;
 use crate::res::DiscordHttpResource;
+use crate::runtime::PendingRequests;
+use crate::state::DiscordConnectionState;
 
 pub(crate) fn handle_b_ready_message(
     mut events: MessageReader<BotReadyMessage>,
+    completed: Res<CompletedRequests>,
+    pending: Res<PendingRequests>,
+    bot_client: Res<BotClient>,
     mut commands: Commands,
 ) {
     for event in events.read() {
-        let http_clone = event.ctx.http.clone();
+        let http = DiscordHttpResource {
+            watched: Some(bot_client.watched.clone()),
+            ..DiscordHttpResource::new(event.ctx.http.clone())
+        };
 
-        commands.insert_resource(DiscordHttpResource::new(http_clone));
+        commands.insert_resource(completed.connect(&pending, http));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_connection_state(
+    mut ready: MessageReader<BotReadyMessage>,
+    mut resume: MessageReader<ResumeMessage>,
+    mut resume_payload: MessageReader<ResumeMessagePayload>,
+    mut shard_stage_update: MessageReader<ShardStageUpdateMessage>,
+    mut disconnected: MessageReader<BotDisconnectedMessage>,
+    mut errors: MessageReader<DiscordBotErrorMessage>,
+    mut restart: MessageReader<BotRestartMessage>,
+    mut next_state: ResMut<NextState<DiscordConnectionState>>,
+) {
+    for event in shard_stage_update.read() {
+        let state = match event.event.new {
+            ConnectionStage::Connected => DiscordConnectionState::Ready,
+            ConnectionStage::Resuming => DiscordConnectionState::Resuming,
+            _ => DiscordConnectionState::Connecting,
+        };
+
+        next_state.set(state);
+    }
+
+    if ready.read().count() > 0 || resume.read().count() > 0 || resume_payload.read().count() > 0 {
+        next_state.set(DiscordConnectionState::Ready);
+    }
+
+    if disconnected.read().count() > 0 {
+        next_state.set(DiscordConnectionState::Disconnected);
+    }
+
+    if errors.read().count() > 0 {
+        next_state.set(DiscordConnectionState::Failed);
+    }
+
+    if restart.read().count() > 0 {
+        next_state.set(DiscordConnectionState::Connecting);
     }
 }
@@ -1,15 +1,116 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
 
-use flume::Sender;
+use flume::SendError;
 use serenity::all::*;
 use tracing::error;
 
+use crate::bot::watchdog::WatchedInteractions;
+use crate::channel::ChannelSender;
 use crate::common::send_message;
+use crate::config::{BotMessageMode, DiscordBotConfig, InteractionWatchdog};
+
+use crate::messages::{ContextReaders, MessageCollectionBot, bot::*};
+
+/// Sends the events to bevy as the messages of the [`BotMessageMode`], followed by their
+/// [`DiscordEvent`] if the stream is enabled, recording them first if it is enabled.
+#[derive(Clone)]
+pub(crate) struct EventSender {
+    pub tx: ChannelSender<MessageCollectionBot>,
+    pub mode: BotMessageMode,
+    /// Messages still written with their context in [`BotMessageMode::Payload`].
+    pub readers: ContextReaders,
+    pub event_stream: bool,
+    /// Sequence of the last [`DiscordEvent`], locked while an event is recorded and queued so
+    /// that the sequence and the recording follow the order they are queued in.
+    pub sequence: Arc<tokio::sync::Mutex<u64>>,
+    #[cfg(feature = "recording")]
+    pub recorder: Option<Arc<crate::recording::EventRecorder>>,
+}
 
-use crate::messages::{MessageCollectionBot, bot::*};
+impl EventSender {
+    pub fn new(
+        tx: ChannelSender<MessageCollectionBot>,
+        config: &DiscordBotConfig,
+        readers: ContextReaders,
+    ) -> Self {
+        Self {
+            tx,
+            mode: config.message_mode,
+            readers,
+            event_stream: config.event_stream,
+            sequence: Default::default(),
+            #[cfg(feature = "recording")]
+            recorder: None,
+        }
+    }
+
+    /// Returns `true` if the gateway events are streamed or recorded, in the order they are
+    /// queued.
+    fn ordered(&self) -> bool {
+        #[cfg(feature = "recording")]
+        if self.recorder.is_some() {
+            return true;
+        }
+
+        self.event_stream
+    }
+
+    pub async fn send_async(
+        &self,
+        message: MessageCollectionBot,
+    ) -> Result<(), SendError<MessageCollectionBot>> {
+        let event = self
+            .ordered()
+            .then(|| DiscordEventKind::from_message(&message))
+            .flatten();
+
+        // Serenity handles every event on its own task, so the events are numbered, recorded and
+        // queued together, in the order the handlers get here
+        let mut sequence = match &event {
+            Some(_) => Some(self.sequence.lock().await),
+            None => None,
+        };
+        let received_at = SystemTime::now();
+
+        #[cfg(feature = "recording")]
+        if let (Some(recorder), Some((shard_id, kind))) = (&self.recorder, &event) {
+            recorder.record(*shard_id, kind, received_at);
+        }
+
+        let (message, payload) = message.with_mode(self.mode, &self.readers);
+        self.tx.send_async(message).await?;
+
+        if let Some(payload) = payload {
+            self.tx.send_async(payload).await?;
+        }
+
+        if let (true, Some(sequence), Some((shard_id, kind))) =
+            (self.event_stream, &mut sequence, event)
+        {
+            **sequence += 1;
+
+            let event = DiscordEvent {
+                sequence: **sequence,
+                shard_id: shard_id.0,
+                received_at,
+                kind,
+            };
+            self.tx.send_async(event.into()).await?;
+        }
+
+        Ok(())
+    }
+}
 
 pub(super) struct Handle {
-    pub tx: Sender<MessageCollectionBot>,
+    pub tx: EventSender,
+    /// Set once a shard is ready, used by the supervisor to reset the restart attempts.
+    pub ready: Arc<AtomicBool>,
+    pub interaction_watchdog: Option<InteractionWatchdog>,
+    pub watched: WatchedInteractions,
 }
 
 #[async_trait]
@@ -480,6 +581,8 @@ impl EventHandler for Handle {
     }
 
     async fn ready(&self, ctx: Context, data_about_bot: Ready) {
+        self.ready.store(true, Ordering::SeqCst);
+
         send_message!(
             self,
             MessageCollectionBot,
@@ -573,6 +676,11 @@ impl EventHandler for Handle {
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Some(watchdog) = &self.interaction_watchdog {
+            self.watched
+                .watch(ctx.http.clone(), &interaction, watchdog, self.tx.tx.clone());
+        }
+
         send_message!(
             self,
             MessageCollectionBot,
@@ -32,20 +32,34 @@
 //!
 //! For HTTP interactions with Discord's API, see the [`http`](crate::http) module.
 
-use bevy_app::{App, Plugin, Startup, Update};
+use bevy_app::{App, Plugin, PostUpdate, Startup, Update};
 use bevy_ecs::prelude::*;
-use serenity::all::*;
+use bevy_state::app::{AppExtStates, StatesPlugin};
+use bevy_state::state::NextState;
 
-use crate::messages::{MessageCollectionBot, bot::*, send_events_bot};
+use crate::messages::payload::add_payload_messages;
+use crate::messages::{
+    ContextReaders, MessageCollectionBot, bot::*, read_with_context, send_events_bot,
+};
 use event_handlers::*;
 
 use crate::DiscordSystems;
-use crate::bot::handle::Handle;
-use crate::channel::ChannelRes;
+use crate::bot::client::{
+    BotClient, apply_bot_presence, shutdown_bot, supervise_client, sync_bot_config,
+};
+use crate::channel::{ChannelRes, DrainBudget};
+use crate::http::request::DiscordRequestPlugin;
+use crate::message_components::ComponentRoutingPlugin;
+use crate::res::MessageQueueStats;
 use crate::runtime::tokio_runtime;
+use crate::state::DiscordConnectionState;
 
+mod client;
 pub(crate) mod event_handlers;
-mod handle;
+pub(crate) mod handle;
+#[cfg(any(feature = "recording", feature = "testing"))]
+pub(crate) mod offline;
+pub(crate) mod watchdog;
 
 /// A plugin that integrates Discord bot functionality into a Bevy application.
 ///
@@ -56,6 +70,11 @@ mod handle;
 /// - Event system integration between Discord and Bevy
 /// - Bot presence and status management
 /// - Gateway connection and communication
+/// - Closing the gateway connections when the app exits
+/// - Tracking the connection in [`DiscordConnectionState`]
+/// - Executing [`DiscordRequest`](crate::messages::http::DiscordRequest) messages
+/// - Routing buttons and select menus to their
+///   [`ComponentHandler`](crate::message_components::ComponentHandler)s
 ///
 /// # Usage
 ///
@@ -81,6 +100,7 @@ mod handle;
 /// - Registers all Discord events as Bevy events
 /// - Manages bot configuration and presence
 /// - Provides asynchronous event handling
+/// - Initializes [`DiscordConnectionState`], adding bevy's `StatesPlugin` if it is missing
 ///
 /// # Note
 ///
@@ -103,13 +123,36 @@ impl DiscordBotPlugin {
 
 impl Plugin for DiscordBotPlugin {
     fn build(&self, app: &mut App) {
-        let (tx, rx) = flume::unbounded::<MessageCollectionBot>();
-        let channel_res = ChannelRes { tx, rx };
-        app.insert_resource(channel_res);
+        let budget = DrainBudget {
+            max_messages: self.0.max_messages_per_frame,
+            max_time: self.0.max_time_per_frame,
+        };
+        let channel_res = ChannelRes::<MessageCollectionBot>::new(
+            self.0.channel_capacity,
+            self.0.overflow_policy.clone(),
+            budget,
+        );
+        app.insert_resource(channel_res)
+            .insert_resource(BotClient::new(self.0.clone()))
+            .init_resource::<ContextReaders>()
+            .init_resource::<MessageQueueStats<DiscordBotPlugin>>();
+
+        if !app.is_plugin_added::<StatesPlugin>() {
+            app.add_plugins(StatesPlugin);
+        }
+        app.init_state::<DiscordConnectionState>();
+
+        if !app.is_plugin_added::<DiscordRequestPlugin>() {
+            app.add_plugins(DiscordRequestPlugin);
+        }
+        app.add_plugins(ComponentRoutingPlugin);
 
         #[cfg(feature = "bot_cache")]
         app.add_message::<CacheReadMessage>()
             .add_message::<ShardsReadyMessage>();
+        add_payload_messages(app);
+        // The HTTP client is taken from the context of the ready event
+        read_with_context::<BotReadyMessage>(app);
 
         app.insert_resource(self.0.clone())
             .add_message::<BotReadyMessage>()
@@ -182,55 +225,73 @@ impl Plugin for DiscordBotPlugin {
             .add_message::<PollVoteAddMessage>()
             .add_message::<PollVoteRemoveMessage>()
             .add_message::<RateLimitMessage>()
+            .add_message::<BotDisconnectedMessage>()
+            .add_message::<DiscordBotErrorMessage>()
+            .add_message::<SetBotPresenceMessage>()
+            .add_message::<BotRestartMessage>()
+            .add_message::<CommandSyncMessage>()
+            .add_message::<UnansweredInteractionMessage>()
+            .add_message::<DiscordEvent>()
             .add_systems(Startup, setup_bot.in_set(DiscordSystems))
             .add_systems(
                 Update,
-                (handle_b_ready_message, send_events_bot)
+                (
+                    handle_b_ready_message,
+                    send_events_bot,
+                    update_connection_state,
+                    apply_bot_presence,
+                    sync_bot_config,
+                )
                     .chain()
                     .in_set(DiscordSystems),
-            );
+            )
+            .add_systems(PostUpdate, shutdown_bot.in_set(DiscordSystems));
     }
 }
 
 fn setup_bot(
-    discord_bot_config: Res<crate::config::DiscordBotConfig>,
     channel_res: Res<ChannelRes<MessageCollectionBot>>,
+    bot_client: Res<BotClient>,
+    readers: Res<ContextReaders>,
+    mut next_state: ResMut<NextState<DiscordConnectionState>>,
+    #[cfg(feature = "recording")] replay: Option<Res<crate::recording::EventReplay>>,
+    #[cfg(feature = "testing")] harness: Option<Res<crate::testing::DiscordTestHarness>>,
 ) {
-    let tx = channel_res.tx.clone();
-
-    let mut client_builder = Client::builder(
-        &discord_bot_config.token,
-        discord_bot_config.gateway_intents,
-    )
-    .event_handler(Handle { tx });
+    #[cfg(feature = "testing")]
+    if harness.is_some() {
+        next_state.set(DiscordConnectionState::Ready);
+        return;
+    }
 
-    let discord_bot_res_clone = discord_bot_config.clone();
+    next_state.set(DiscordConnectionState::Connecting);
 
-    if let Some(status) = discord_bot_res_clone.status {
-        client_builder = client_builder.status(status);
-    }
+    #[cfg(feature = "recording")]
+    if let Some(replay) = replay {
+        let (http, events) = {
+            let config = bot_client
+                .config
+                .lock()
+                .expect("config lock shouldn't be poisoned");
+            (
+                crate::http::discord_http(&config.token, config.base_url.as_deref()),
+                handle::EventSender::new(channel_res.tx.clone(), &config, readers.clone()),
+            )
+        };
 
-    if let Some(activity) = discord_bot_res_clone.activity {
-        client_builder = client_builder.activity(activity);
+        tokio_runtime().spawn(crate::recording::replay_events(
+            events,
+            replay.clone(),
+            http,
+        ));
+        return;
     }
 
-    let discord_bot_config_clone = discord_bot_config.clone();
-
-    tokio_runtime().spawn(async move {
-        let mut client = client_builder
-            .await
-            .expect("Unable to build discord Client");
-
-        if discord_bot_config_clone.shards == 0 {
-            client
-                .start()
-                .await
-                .expect("Unable to run the discord Client");
-        } else {
-            client
-                .start_shards(discord_bot_config_clone.shards)
-                .await
-                .expect("Unable to run the discord Client with multiple shards.")
-        }
-    });
+    tokio_runtime().spawn(supervise_client(
+        channel_res.tx.clone(),
+        bot_client.config.clone(),
+        bot_client.shard_manager.clone(),
+        bot_client.shutdown.clone(),
+        bot_client.watched.clone(),
+        readers.clone(),
+    ));
 }
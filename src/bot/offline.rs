@@ -0,0 +1,73 @@
+use serenity::all::{
+    Cache, Context, GatewayIntents, Http, ShardId, ShardInfo, ShardManager, ShardManagerOptions,
+    ShardMessenger, ShardRunner, ShardRunnerOptions,
+};
+use serenity::gateway::Shard;
+use std::sync::{Arc, OnceLock};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, RwLock};
+
+/// Builds a [`Context`] that isn't connected to discord, for the events that are replayed.
+///
+/// Serenity only creates a shard messenger from a connected shard, so the shard connects to a
+/// websocket served locally that never sends anything. Messages sent to the shard are dropped.
+pub(crate) async fn offline_context(http: Arc<Http>) -> serenity::Result<Context> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let ws_url = Arc::new(Mutex::new(format!("ws://{}", listener.local_addr()?)));
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        tokio_tungstenite::accept_async(stream)
+            .await
+            .map_err(std::io::Error::other)
+    });
+
+    let shard = Shard::new(
+        ws_url.clone(),
+        http.token(),
+        ShardInfo {
+            id: ShardId(0),
+            total: 1,
+        },
+        GatewayIntents::empty(),
+        None,
+    )
+    .await?;
+    // The shard never runs, so the websocket can be closed right away
+    let _ = server.await;
+
+    let data = Arc::new(RwLock::new(Default::default()));
+    let cache = Arc::new(Cache::new());
+    let (manager, _) = ShardManager::new(ShardManagerOptions {
+        data: data.clone(),
+        event_handlers: Vec::new(),
+        raw_event_handlers: Vec::new(),
+        framework: Arc::new(OnceLock::new()),
+        shard_index: 0,
+        shard_init: 0,
+        shard_total: 1,
+        ws_url,
+        cache: cache.clone(),
+        http: http.clone(),
+        intents: GatewayIntents::empty(),
+        presence: None,
+    });
+    let runner = ShardRunner::new(ShardRunnerOptions {
+        data: data.clone(),
+        event_handlers: Vec::new(),
+        raw_event_handlers: Vec::new(),
+        framework: None,
+        manager,
+        shard,
+        cache: cache.clone(),
+        http: http.clone(),
+    });
+
+    Ok(Context {
+        data,
+        shard: ShardMessenger::new(&runner),
+        shard_id: ShardId(0),
+        http,
+        cache,
+    })
+}
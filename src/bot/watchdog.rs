@@ -0,0 +1,332 @@
+use serenity::all::{
+    Builder, CreateInteractionResponse, CreateInteractionResponseMessage, Http, Interaction,
+    InteractionId, InteractionType,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+use tracing::error;
+
+use crate::channel::ChannelSender;
+use crate::config::InteractionWatchdog;
+use crate::messages::{MessageCollectionBot, bot::UnansweredInteractionMessage};
+use crate::runtime::tokio_runtime;
+
+/// Discord fails the interaction if it isn't answered within this time.
+const RESPONSE_DEADLINE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Pending,
+    /// The watchdog is deferring the interaction, but discord hasn't confirmed it yet.
+    Deferring,
+    Deferred,
+}
+
+struct Tracked {
+    state: State,
+    kind: InteractionType,
+    /// Notified once the defer has either succeeded or failed.
+    deferred: Arc<Notify>,
+    /// Task deferring and reporting the interaction, aborted once it is answered.
+    task: Option<AbortHandle>,
+}
+
+/// How a response to a tracked interaction has to be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Claim {
+    /// The interaction hasn't been answered yet, or isn't tracked.
+    Respond,
+    /// The interaction has already been deferred by the watchdog.
+    Deferred(InteractionType),
+}
+
+/// Interactions that are watched by a bot client, shared with the executor of
+/// [`DiscordRequest`](crate::messages::http::DiscordRequest) through
+/// [`DiscordHttpResource`](crate::res::DiscordHttpResource).
+#[derive(Clone, Default)]
+pub(crate) struct WatchedInteractions(Arc<Mutex<HashMap<InteractionId, Tracked>>>);
+
+impl std::fmt::Debug for WatchedInteractions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedInteractions")
+            .finish_non_exhaustive()
+    }
+}
+
+impl WatchedInteractions {
+    fn lock(&self) -> MutexGuard<'_, HashMap<InteractionId, Tracked>> {
+        self.0
+            .lock()
+            .expect("watched interactions lock shouldn't be poisoned")
+    }
+
+    /// Watches the interaction, deferring it if it isn't answered in time and reporting it if it
+    /// is never answered.
+    pub(crate) fn watch(
+        &self,
+        http: Arc<Http>,
+        interaction: &Interaction,
+        watchdog: &InteractionWatchdog,
+        tx: ChannelSender<MessageCollectionBot>,
+    ) {
+        let (id, token) = match interaction {
+            Interaction::Command(interaction) => (interaction.id, &interaction.token),
+            Interaction::Component(interaction) => (interaction.id, &interaction.token),
+            Interaction::Modal(interaction) => (interaction.id, &interaction.token),
+            // Autocompletes and pings can't be deferred
+            _ => return,
+        };
+        let kind = interaction.kind();
+
+        self.lock().insert(
+            id,
+            Tracked {
+                state: State::Pending,
+                kind,
+                deferred: Arc::default(),
+                task: None,
+            },
+        );
+
+        let watched = self.clone();
+        let token = token.clone();
+        let watchdog = watchdog.clone();
+
+        let task = tokio_runtime().spawn(async move {
+            let Some(defer_after) = watchdog.defer_after else {
+                tokio::time::sleep(RESPONSE_DEADLINE).await;
+                watched.report_unanswered(id, kind, &tx).await;
+                return;
+            };
+
+            tokio::time::sleep(defer_after).await;
+
+            if watched.transition(id, State::Pending, State::Deferring) {
+                let response = match kind {
+                    InteractionType::Component => CreateInteractionResponse::Acknowledge,
+                    _ => CreateInteractionResponse::Defer(
+                        CreateInteractionResponseMessage::new().ephemeral(watchdog.ephemeral),
+                    ),
+                };
+
+                // A failed defer leaves the interaction to be answered normally
+                let state = match response.execute(http.as_ref(), (id, &token)).await {
+                    Ok(()) => State::Deferred,
+                    Err(err) => {
+                        error!("Unable to defer the interaction {}: {}", id, err);
+                        State::Pending
+                    }
+                };
+                watched.transition(id, State::Deferring, state);
+            }
+
+            tokio::time::sleep(watchdog.expire_after.saturating_sub(defer_after)).await;
+            watched.report_unanswered(id, kind, &tx).await;
+        });
+
+        // The interaction may already have been answered
+        match self.lock().get_mut(&id) {
+            Some(tracked) => tracked.task = Some(task.abort_handle()),
+            None => task.abort(),
+        }
+    }
+
+    /// Stops watching the interaction, and reports it since it wasn't answered.
+    async fn report_unanswered(
+        &self,
+        id: InteractionId,
+        kind: InteractionType,
+        tx: &ChannelSender<MessageCollectionBot>,
+    ) {
+        let Some(tracked) = self.lock().remove(&id) else {
+            return;
+        };
+
+        let message = UnansweredInteractionMessage {
+            interaction_id: id,
+            kind,
+            deferred: tracked.state == State::Deferred,
+        };
+
+        if tx
+            .send_async(MessageCollectionBot::UnansweredInteractionMessage(message))
+            .await
+            .is_err()
+        {
+            error!("Unable to send event to the channel")
+        }
+    }
+
+    /// Moves the interaction from one state to another, returns `false` if it isn't in `from`.
+    fn transition(&self, id: InteractionId, from: State, to: State) -> bool {
+        let mut tracked = self.lock();
+        let Some(tracked) = tracked.get_mut(&id).filter(|tracked| tracked.state == from) else {
+            return false;
+        };
+
+        tracked.state = to;
+        if from == State::Deferring {
+            tracked.deferred.notify_waiters();
+        }
+        true
+    }
+
+    /// Stops watching the interaction since it is being answered, and returns how the response
+    /// has to be sent.
+    ///
+    /// Waits for a defer that is still in flight, so that the response isn't sent as an edit
+    /// before discord has the deferred response.
+    pub(crate) async fn claim(&self, id: InteractionId) -> Claim {
+        loop {
+            let notified = {
+                let mut watched = self.lock();
+                let Some(tracked) = watched.get(&id) else {
+                    return Claim::Respond;
+                };
+
+                let claim = match tracked.state {
+                    State::Deferring => None,
+                    State::Deferred => Some(Claim::Deferred(tracked.kind)),
+                    State::Pending => Some(Claim::Respond),
+                };
+
+                if let Some(claim) = claim {
+                    if let Some(task) = watched.remove(&id).and_then(|tracked| tracked.task) {
+                        task.abort();
+                    }
+                    return claim;
+                }
+
+                // Registered before the lock is released, so that the notification can't be
+                // missed
+                let mut notified = Box::pin(tracked.deferred.clone().notified_owned());
+                notified.as_mut().enable();
+                notified
+            };
+
+            notified.await;
+        }
+    }
+}
+
+/// Sends the response to an interaction that was deferred by the watchdog.
+///
+/// Messages become follow-ups, or edit the original response if it was the deferred one, and
+/// message updates edit the original message. Attachments aren't sent.
+pub(crate) async fn respond_deferred(
+    http: &Http,
+    token: &str,
+    kind: InteractionType,
+    response: CreateInteractionResponse,
+) -> serenity::Result<()> {
+    match (response, kind) {
+        (CreateInteractionResponse::Message(message), InteractionType::Component) => {
+            http.create_followup_message(token, &message, Vec::new())
+                .await?;
+        }
+        (
+            CreateInteractionResponse::Message(message)
+            | CreateInteractionResponse::UpdateMessage(message),
+            _,
+        ) => {
+            http.edit_original_interaction_response(token, &message, Vec::new())
+                .await?;
+        }
+        // Already done by the watchdog
+        (CreateInteractionResponse::Defer(_) | CreateInteractionResponse::Acknowledge, _) => {}
+        _ => {
+            return Err(serenity::Error::Other(
+                "the interaction was deferred before it was answered, the response can't be sent",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(watched: &WatchedInteractions, id: InteractionId, state: State) {
+        watched.lock().insert(
+            id,
+            Tracked {
+                state,
+                kind: InteractionType::Command,
+                deferred: Arc::default(),
+                task: None,
+            },
+        );
+    }
+
+    #[test]
+    fn claim_waits_for_a_defer_in_flight() {
+        let watched = WatchedInteractions::default();
+        let id = InteractionId::new(1);
+        track(&watched, id, State::Deferring);
+
+        let claim = tokio_runtime().spawn({
+            let watched = watched.clone();
+            async move { watched.claim(id).await }
+        });
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!claim.is_finished());
+
+        assert!(watched.transition(id, State::Deferring, State::Deferred));
+        assert_eq!(
+            tokio_runtime().block_on(claim).unwrap(),
+            Claim::Deferred(InteractionType::Command)
+        );
+    }
+
+    #[test]
+    fn claim_responds_after_a_failed_defer() {
+        let watched = WatchedInteractions::default();
+        let id = InteractionId::new(1);
+        track(&watched, id, State::Deferring);
+
+        let claim = tokio_runtime().spawn({
+            let watched = watched.clone();
+            async move { watched.claim(id).await }
+        });
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(watched.transition(id, State::Deferring, State::Pending));
+        assert_eq!(tokio_runtime().block_on(claim).unwrap(), Claim::Respond);
+    }
+
+    #[test]
+    fn clients_watch_their_own_interactions() {
+        let first = WatchedInteractions::default();
+        let second = WatchedInteractions::default();
+        let id = InteractionId::new(1);
+        track(&first, id, State::Deferred);
+
+        let claims =
+            tokio_runtime().block_on(async { (second.claim(id).await, first.claim(id).await) });
+        assert_eq!(
+            claims,
+            (Claim::Respond, Claim::Deferred(InteractionType::Command))
+        );
+    }
+
+    #[test]
+    fn answered_interactions_stop_being_watched() {
+        let watched = WatchedInteractions::default();
+        let id = InteractionId::new(1);
+        track(&watched, id, State::Pending);
+
+        let task =
+            tokio_runtime().spawn(async { tokio::time::sleep(Duration::from_secs(60)).await });
+        watched.lock().get_mut(&id).unwrap().task = Some(task.abort_handle());
+
+        assert_eq!(tokio_runtime().block_on(watched.claim(id)), Claim::Respond);
+        assert!(watched.lock().is_empty());
+        assert!(tokio_runtime().block_on(task).unwrap_err().is_cancelled());
+    }
+}
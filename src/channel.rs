@@ -1,11 +1,249 @@
 //! Internal Channel Plugin, that just add
 //! channel resource
 
+use crate::config::OverflowPolicy;
 use bevy_ecs::prelude::Resource;
-use flume::{Receiver, Sender};
+use flume::{Receiver, SendError, Sender, TrySendError};
+use std::any::TypeId;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
+type Queue<T> = Arc<Mutex<VecDeque<T>>>;
+
+fn lock<T>(queue: &Queue<T>) -> MutexGuard<'_, VecDeque<T>> {
+    queue.lock().expect("channel lock shouldn't be poisoned")
+}
+
+/// Queue of the events waiting to be forwarded to bevy.
+///
+/// The events are kept in a [`VecDeque`] so that the [`OverflowPolicy`] can evict any of them,
+/// while the `slots` channel holds one token per queued event and makes the senders wait when
+/// the queue is full.
 #[derive(Resource)]
 pub struct ChannelRes<T> {
-    pub rx: Receiver<T>,
-    pub tx: Sender<T>,
+    queue: Queue<T>,
+    slots: Receiver<()>,
+    pub tx: ChannelSender<T>,
+    pub budget: DrainBudget,
+    pub capacity: Option<usize>,
+}
+
+impl<T> ChannelRes<T> {
+    /// Creates the channel, it is unbounded unless a non-zero `capacity` is given.
+    pub fn new(capacity: Option<usize>, policy: OverflowPolicy, budget: DrainBudget) -> Self {
+        let capacity = capacity.filter(|capacity| *capacity > 0);
+        let (slots_tx, slots) = match capacity {
+            Some(capacity) => flume::bounded(capacity),
+            None => flume::unbounded(),
+        };
+        let queue = Queue::default();
+
+        Self {
+            tx: ChannelSender {
+                queue: queue.clone(),
+                slots: slots_tx,
+                policy: Arc::new(policy),
+                dropped: Arc::new(AtomicU64::new(0)),
+            },
+            queue,
+            slots,
+            budget,
+            capacity,
+        }
+    }
+
+    /// Removes the oldest event, if any.
+    pub fn try_recv(&self) -> Option<T> {
+        let message = lock(&self.queue).pop_front()?;
+        // Frees the slot of the event for the next one
+        let _ = self.slots.try_recv();
+        Some(message)
+    }
+
+    /// Number of events waiting to be forwarded.
+    pub fn len(&self) -> usize {
+        lock(&self.queue).len()
+    }
+
+    /// Total number of events dropped because the channel was full.
+    pub fn dropped(&self) -> u64 {
+        self.tx.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Implemented by the message collections so that [`OverflowPolicy::DropTypesFirst`] can tell
+/// the events apart.
+pub trait QueuedMessage {
+    fn message_type_id(&self) -> TypeId;
+}
+
+/// Sending half of [`ChannelRes`] which applies the [`OverflowPolicy`] when the channel is full.
+pub struct ChannelSender<T> {
+    queue: Queue<T>,
+    slots: Sender<()>,
+    policy: Arc<OverflowPolicy>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            slots: self.slots.clone(),
+            policy: self.policy.clone(),
+            dropped: self.dropped.clone(),
+        }
+    }
+}
+
+impl<T: QueuedMessage> ChannelSender<T> {
+    /// Sends the event, waiting for space or dropping events as configured by the policy.
+    pub async fn send_async(&self, message: T) -> Result<(), SendError<T>> {
+        match self.slots.try_send(()) {
+            Ok(()) => {
+                lock(&self.queue).push_back(message);
+                return Ok(());
+            }
+            Err(TrySendError::Disconnected(())) => return Err(SendError(message)),
+            Err(TrySendError::Full(())) => {}
+        }
+
+        // The queue is full, the new event either replaces a queued one, keeping its slot, or
+        // waits for a free slot
+        let message = match self.policy.as_ref() {
+            OverflowPolicy::Block => message,
+            OverflowPolicy::DropNewest => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                return Ok(());
+            }
+            OverflowPolicy::DropOldest => {
+                let mut queue = lock(&self.queue);
+                if queue.pop_front().is_none() {
+                    // Every slot is taken by an event that is about to be queued
+                    drop(queue);
+                    message
+                } else {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    queue.push_back(message);
+                    return Ok(());
+                }
+            }
+            OverflowPolicy::DropTypesFirst(types) => {
+                if types.contains(&message.message_type_id()) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+
+                let mut queue = lock(&self.queue);
+                let droppable = queue
+                    .iter()
+                    .position(|queued| types.contains(&queued.message_type_id()));
+                if let Some(index) = droppable {
+                    queue.remove(index);
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    queue.push_back(message);
+                    return Ok(());
+                }
+                drop(queue);
+                message
+            }
+        };
+
+        if self.slots.send_async(()).await.is_err() {
+            return Err(SendError(message));
+        }
+        lock(&self.queue).push_back(message);
+        Ok(())
+    }
+}
+
+/// Limits how much of the channel is forwarded to bevy in a single frame, a limit of `0` is
+/// the same as no limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DrainBudget {
+    pub max_messages: Option<usize>,
+    pub max_time: Option<Duration>,
+}
+
+impl DrainBudget {
+    /// Returns `true` if another message can be forwarded in the current frame.
+    pub fn allows(&self, forwarded: usize, started: Instant) -> bool {
+        self.max_messages
+            .is_none_or(|max| max == 0 || forwarded < max)
+            && self
+                .max_time
+                .is_none_or(|max| max.is_zero() || started.elapsed() < max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::tokio_runtime;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Typing(u32),
+        Message(u32),
+    }
+
+    impl QueuedMessage for Event {
+        fn message_type_id(&self) -> TypeId {
+            match self {
+                Event::Typing(_) => TypeId::of::<u8>(),
+                Event::Message(_) => TypeId::of::<u16>(),
+            }
+        }
+    }
+
+    fn drain(channel: &ChannelRes<Event>) -> Vec<Event> {
+        std::iter::from_fn(|| channel.try_recv()).collect()
+    }
+
+    #[test]
+    fn drop_types_first_evicts_queued_events() {
+        let policy = OverflowPolicy::DropTypesFirst(vec![TypeId::of::<u8>()]);
+        let channel = ChannelRes::new(Some(2), policy, DrainBudget::default());
+
+        tokio_runtime().block_on(async {
+            channel.tx.send_async(Event::Typing(1)).await.unwrap();
+            channel.tx.send_async(Event::Message(2)).await.unwrap();
+            channel.tx.send_async(Event::Message(3)).await.unwrap();
+            channel.tx.send_async(Event::Typing(4)).await.unwrap();
+        });
+
+        assert_eq!(drain(&channel), [Event::Message(2), Event::Message(3)]);
+        assert_eq!(channel.dropped(), 2);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_newest_events() {
+        let channel = ChannelRes::new(Some(2), OverflowPolicy::DropOldest, DrainBudget::default());
+
+        tokio_runtime().block_on(async {
+            for id in 0..5 {
+                channel.tx.send_async(Event::Message(id)).await.unwrap();
+            }
+        });
+
+        assert_eq!(drain(&channel), [Event::Message(3), Event::Message(4)]);
+        assert_eq!(channel.dropped(), 3);
+    }
+
+    #[test]
+    fn zero_capacity_is_unbounded() {
+        let channel = ChannelRes::new(Some(0), OverflowPolicy::DropOldest, DrainBudget::default());
+
+        tokio_runtime().block_on(async {
+            for id in 0..3 {
+                channel.tx.send_async(Event::Message(id)).await.unwrap();
+            }
+        });
+
+        assert_eq!(channel.capacity, None);
+        assert_eq!(channel.len(), 3);
+        assert_eq!(channel.dropped(), 0);
+    }
 }
@@ -0,0 +1,397 @@
+//! Checks evaluated before a command is dispatched to its handlers.
+//!
+//! Attach [`CommandChecks`] to a slash command with
+//! [`DiscordCommandAppExt::add_discord_command_checks`](crate::slash_commands::DiscordCommandAppExt::add_discord_command_checks),
+//! or to a prefix command with
+//! [`PrefixCommand::checks`](crate::prefix_commands::PrefixCommand::checks). A command that
+//! fails a check isn't dispatched, the user is told why, and a [`CommandRejected`] is written.
+//!
+//! The checks of a slash command also apply to its subcommands, e.g. the checks of `admin` to
+//! `admin ban`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::checks::{CommandChecks, CommandRejected, Cooldown};
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::serenity::all::Permissions;
+//! use bevy_discord::slash_commands::DiscordCommandAppExt;
+//! use std::time::Duration;
+//!
+//! fn log_rejections(mut rejected: MessageReader<CommandRejected>) {
+//!     for rejected in rejected.read() {
+//!         info!("/{} was rejected: {}", rejected.command, rejected.reason);
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_discord_command_checks(
+//!         "admin",
+//!         CommandChecks::default()
+//!             .guild_only()
+//!             .required_permissions(Permissions::BAN_MEMBERS)
+//!             .cooldown(Cooldown::per_user(Duration::from_secs(10))),
+//!     )
+//!     .add_systems(Update, log_rejections)
+//!     .run();
+//! ```
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::*;
+use serenity::all::{ChannelId, GuildId, Permissions, RoleId, UserId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Which uses of a command share a [`Cooldown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CooldownBucket {
+    User,
+    Channel,
+    /// Uses in DMs share the cooldown of their channel.
+    Guild,
+}
+
+/// Minimum time between two uses of a command in the same bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cooldown {
+    pub duration: Duration,
+    pub bucket: CooldownBucket,
+}
+
+impl Cooldown {
+    pub fn per_user(duration: Duration) -> Self {
+        Self {
+            duration,
+            bucket: CooldownBucket::User,
+        }
+    }
+
+    pub fn per_channel(duration: Duration) -> Self {
+        Self {
+            duration,
+            bucket: CooldownBucket::Channel,
+        }
+    }
+
+    pub fn per_guild(duration: Duration) -> Self {
+        Self {
+            duration,
+            bucket: CooldownBucket::Guild,
+        }
+    }
+}
+
+/// Checks that have to pass before a command is dispatched, see the [module](self) for more
+/// information.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct CommandChecks {
+    cooldowns: Vec<Cooldown>,
+    permissions: Permissions,
+    roles: Vec<RoleId>,
+    owner_only: bool,
+    guild_only: bool,
+    dm_only: bool,
+    respond: bool,
+}
+
+impl Default for CommandChecks {
+    fn default() -> Self {
+        Self {
+            cooldowns: Vec::new(),
+            permissions: Permissions::empty(),
+            roles: Vec::new(),
+            owner_only: false,
+            guild_only: false,
+            dm_only: false,
+            respond: true,
+        }
+    }
+}
+
+impl CommandChecks {
+    /// Adds a cooldown, a command can have one per bucket.
+    pub fn cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldowns.push(cooldown);
+        self
+    }
+
+    /// Requires the user to have the permissions in the guild, which implies
+    /// [`guild_only`](Self::guild_only).
+    ///
+    /// The permissions of prefix command users are only known with the `bot_cache` feature,
+    /// without it the check always fails.
+    pub fn required_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions |= permissions;
+        self
+    }
+
+    /// Requires the user to have the role, which implies [`guild_only`](Self::guild_only).
+    pub fn required_role(mut self, role_id: RoleId) -> Self {
+        self.roles.push(role_id);
+        self
+    }
+
+    /// Only lets the users in [`CommandOwners`] use the command.
+    pub fn owner_only(mut self) -> Self {
+        self.owner_only = true;
+        self
+    }
+
+    pub fn guild_only(mut self) -> Self {
+        self.guild_only = true;
+        self
+    }
+
+    pub fn dm_only(mut self) -> Self {
+        self.dm_only = true;
+        self
+    }
+
+    /// Doesn't tell the user why the command was rejected, [`CommandRejected`] is still written.
+    pub fn silent(mut self) -> Self {
+        self.respond = false;
+        self
+    }
+
+    pub(crate) fn responds(&self) -> bool {
+        self.respond
+    }
+
+    /// Evaluates the checks, and returns the cooldowns to start once every check of the
+    /// command, including those of its parent commands, has passed.
+    pub(crate) fn evaluate(
+        &self,
+        command: &str,
+        context: &CheckContext,
+        owners: &CommandOwners,
+        cooldowns: &CommandCooldowns,
+    ) -> Result<Vec<(CooldownKey, Duration)>, RejectionReason> {
+        if self.owner_only && !owners.0.contains(&context.user_id) {
+            return Err(RejectionReason::NotOwner);
+        }
+
+        let needs_guild = self.guild_only || !self.permissions.is_empty() || !self.roles.is_empty();
+        if needs_guild && context.guild_id.is_none() {
+            return Err(RejectionReason::GuildOnly);
+        }
+
+        if self.dm_only && context.guild_id.is_some() {
+            return Err(RejectionReason::DmOnly);
+        }
+
+        if !self.permissions.is_empty() {
+            let permissions = context.permissions.unwrap_or_else(Permissions::empty);
+
+            if !permissions.contains(self.permissions) {
+                return Err(RejectionReason::MissingPermissions(
+                    self.permissions - permissions,
+                ));
+            }
+        }
+
+        if let Some(role_id) = self
+            .roles
+            .iter()
+            .find(|role_id| !context.roles.contains(role_id))
+        {
+            return Err(RejectionReason::MissingRole(*role_id));
+        }
+
+        let now = Instant::now();
+        let keys: Vec<_> = self
+            .cooldowns
+            .iter()
+            .map(|cooldown| {
+                let bucket = match cooldown.bucket {
+                    CooldownBucket::User => context.user_id.get(),
+                    CooldownBucket::Channel => context.channel_id.get(),
+                    CooldownBucket::Guild => context
+                        .guild_id
+                        .map(GuildId::get)
+                        .unwrap_or(context.channel_id.get()),
+                };
+
+                (
+                    (command.to_string(), cooldown.bucket, bucket),
+                    cooldown.duration,
+                )
+            })
+            .collect();
+
+        for (key, _) in &keys {
+            if let Some(remaining) = cooldowns
+                .0
+                .get(key)
+                .and_then(|expires| expires.checked_duration_since(now))
+                .filter(|remaining| !remaining.is_zero())
+            {
+                return Err(RejectionReason::Cooldown { remaining });
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+/// What the checks know about the use of a command.
+#[derive(Debug, Clone)]
+pub(crate) struct CheckContext {
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub permissions: Option<Permissions>,
+    pub roles: Vec<RoleId>,
+}
+
+/// Users allowed to use the commands with [`CommandChecks::owner_only`], empty by default.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CommandOwners(pub HashSet<UserId>);
+
+/// A command and the bucket it is on cooldown for.
+pub(crate) type CooldownKey = (String, CooldownBucket, u64);
+
+/// When the cooldown of each command ends, by cooldown bucket.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct CommandCooldowns(HashMap<CooldownKey, Instant>);
+
+impl CommandCooldowns {
+    /// Starts the cooldowns returned by [`CommandChecks::evaluate`], and forgets the ones that
+    /// have ended.
+    pub(crate) fn start(&mut self, cooldowns: impl IntoIterator<Item = (CooldownKey, Duration)>) {
+        let now = Instant::now();
+        self.0.retain(|_, expires| *expires > now);
+
+        for (key, duration) in cooldowns {
+            self.0.insert(key, now + duration);
+        }
+    }
+}
+
+/// Why a command was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// The command is on cooldown.
+    Cooldown { remaining: Duration },
+    /// The user lacks these permissions.
+    MissingPermissions(Permissions),
+    /// The user lacks this role.
+    MissingRole(RoleId),
+    /// The user isn't in [`CommandOwners`].
+    NotOwner,
+    /// The command was used in DMs.
+    GuildOnly,
+    /// The command was used in a guild.
+    DmOnly,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cooldown { remaining } => write!(
+                f,
+                "This command is on cooldown, try again in {} seconds.",
+                remaining.as_secs().max(1)
+            ),
+            Self::MissingPermissions(permissions) => write!(
+                f,
+                "You need the following permissions to use this command: {}.",
+                permissions.get_permission_names().join(", ")
+            ),
+            Self::MissingRole(role_id) => {
+                write!(f, "You need the <@&{role_id}> role to use this command.")
+            }
+            Self::NotOwner => write!(f, "Only the owners of the bot can use this command."),
+            Self::GuildOnly => write!(f, "This command can only be used in a server."),
+            Self::DmOnly => write!(f, "This command can only be used in direct messages."),
+        }
+    }
+}
+
+/// Written whenever a command fails its [`CommandChecks`].
+#[derive(Message, Debug, Clone)]
+pub struct CommandRejected {
+    /// Path of the slash command, or name of the prefix command.
+    pub command: String,
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub reason: RejectionReason,
+}
+
+impl CommandRejected {
+    pub(crate) fn new(command: &str, context: &CheckContext, reason: RejectionReason) -> Self {
+        Self {
+            command: command.to_string(),
+            user_id: context.user_id,
+            channel_id: context.channel_id,
+            guild_id: context.guild_id,
+            reason,
+        }
+    }
+}
+
+/// Registers [`CommandRejected`] and the resources of the checks, added by the routing of the
+/// slash and prefix commands.
+pub(crate) struct CommandChecksPlugin;
+
+impl Plugin for CommandChecksPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<CommandRejected>()
+            .init_resource::<CommandOwners>()
+            .init_resource::<CommandCooldowns>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> CheckContext {
+        CheckContext {
+            user_id: UserId::new(1),
+            channel_id: ChannelId::new(2),
+            guild_id: None,
+            permissions: None,
+            roles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn cooldowns_only_start_once_started() {
+        let checks = CommandChecks::default().cooldown(Cooldown::per_user(Duration::from_secs(60)));
+        let owners = CommandOwners::default();
+        let mut cooldowns = CommandCooldowns::default();
+
+        let started = checks
+            .evaluate("admin", &context(), &owners, &cooldowns)
+            .unwrap();
+        // Not started yet, e.g. because the checks of a subcommand may still fail
+        assert!(
+            checks
+                .evaluate("admin", &context(), &owners, &cooldowns)
+                .is_ok()
+        );
+
+        cooldowns.start(started);
+        assert!(matches!(
+            checks.evaluate("admin", &context(), &owners, &cooldowns),
+            Err(RejectionReason::Cooldown { .. })
+        ));
+    }
+
+    #[test]
+    fn ended_cooldowns_are_forgotten() {
+        let mut cooldowns = CommandCooldowns::default();
+        let key = |command: &str| (command.to_string(), CooldownBucket::User, 1);
+
+        cooldowns.start([(key("ping"), Duration::ZERO)]);
+        cooldowns.start([(key("ban"), Duration::from_secs(60))]);
+
+        assert!(!cooldowns.0.contains_key(&key("ping")));
+        assert!(cooldowns.0.contains_key(&key("ban")));
+    }
+}
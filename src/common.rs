@@ -24,12 +24,13 @@ macro_rules! create_message_collection_and_handler {
     (
         $name:ident,
         $fn_name:ident,
+        $plugin:ty,
         $(
             $(#[$meta:meta])? $variant:ident
         ),* $(,)?
     ) => {
         // Define the enum with the provided variants
-        #[allow(clippy::enum_variant_names)]
+        #[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
         pub(crate) enum $name {
             $(
                 $(#[$meta])?
@@ -37,6 +38,26 @@ macro_rules! create_message_collection_and_handler {
             )*
         }
 
+        $(
+            $(#[$meta])?
+            impl From<$variant> for $name {
+                fn from(message: $variant) -> Self {
+                    $name::$variant(message)
+                }
+            }
+        )*
+
+        impl $crate::channel::QueuedMessage for $name {
+            fn message_type_id(&self) -> std::any::TypeId {
+                match self {
+                    $(
+                        $(#[$meta])?
+                        $name::$variant(_) => std::any::TypeId::of::<$variant>(),
+                    )*
+                }
+            }
+        }
+
         pastey::paste! {
             #[derive(bevy_ecs::system::SystemParam)]
             pub(crate) struct [< $name SystemParam >]<'w> {
@@ -46,12 +67,21 @@ macro_rules! create_message_collection_and_handler {
                 )*
             }
 
-            // Define the function to handle the events and send them through EventWriter
+            // Define the function to handle the events and send them through EventWriter.
+            // The channel is drained in order until it is empty or the frame budget runs out.
             pub(crate) fn [<send_events_ $fn_name>](
                 channel: bevy_ecs::prelude::Res<$crate::channel::ChannelRes<$name>>,
+                mut stats: bevy_ecs::prelude::ResMut<$crate::res::MessageQueueStats<$plugin>>,
                 mut events_system_param: [< $name SystemParam >]
             ) {
-                if let Ok(event) = channel.rx.try_recv() {
+                let started = std::time::Instant::now();
+                let mut forwarded = 0;
+
+                while channel.budget.allows(forwarded, started) {
+                    let Some(event) = channel.try_recv() else {
+                        break;
+                    };
+
                     match event {
                         $(
                             $(#[$meta])?
@@ -60,7 +90,14 @@ macro_rules! create_message_collection_and_handler {
                             }
                         ),*
                     }
+
+                    forwarded += 1;
                 }
+
+                stats.forwarded = forwarded;
+                stats.pending = channel.len();
+                stats.capacity = channel.capacity;
+                stats.dropped = channel.dropped();
             }
         }
     };
@@ -88,8 +125,8 @@ macro_rules! send_message_tuple {
     };
 }
 
-// TODO: Should we use `allow(unused_imports)` as not all the macros are used with
-// multiple feature combinations
+// Not all the macros are used with every feature combination
+#[allow(unused_imports)]
 pub(crate) use {
     create_message_collection_and_handler, initialize_field_with_doc, override_field_with_doc,
     send_message, send_message_tuple,
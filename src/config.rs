@@ -5,12 +5,68 @@
 //!
 //! - [`DiscordBotConfig`]: Configuration for the Discord bot (available with `bot` feature)
 //! - [`DiscordRichPresenceConfig`]: Configuration for Rich Presence integration (available with `rich_presence` feature)
+//!
+//! Both of them share the [`OverflowPolicy`] that decides what happens when the queue between
+//! Discord and Bevy is full.
 
-use crate::common::initialize_field_with_doc;
+use crate::common::{initialize_field_with_doc, override_field_with_doc};
+use bevy_ecs::message::Message;
 use bevy_ecs::prelude::*;
+use std::any::TypeId;
+use std::time::Duration;
+
+/// Decides what happens to a new Discord event when the queue that carries events into Bevy
+/// has reached its capacity.
+///
+/// The policy only applies once the queue is full, which never happens unless a capacity is set
+/// with [`DiscordBotConfig::channel_capacity`] or
+/// [`DiscordRichPresenceConfig::channel_capacity`], the queue is unbounded otherwise.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy_discord::config::{DiscordBotConfig, OverflowPolicy};
+/// use bevy_discord::messages::bot::{PresenceUpdateMessage, TypingStartMessage};
+///
+/// let config = DiscordBotConfig::default()
+///     .channel_capacity(1024)
+///     .overflow_policy(
+///         OverflowPolicy::drop_first::<TypingStartMessage>().and_drop::<PresenceUpdateMessage>(),
+///     );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Waits in the Discord event handler until Bevy has made space in the queue.
+    #[default]
+    Block,
+    /// Removes the oldest queued event to make space for the new one.
+    DropOldest,
+    /// Discards the new event.
+    DropNewest,
+    /// Discards new events of the listed message types, every other event replaces the oldest
+    /// queued event of the listed types, or waits like [`OverflowPolicy::Block`] if there is
+    /// none. Use [`OverflowPolicy::drop_first`] to create it.
+    DropTypesFirst(Vec<TypeId>),
+}
+
+impl OverflowPolicy {
+    /// Creates a [`OverflowPolicy::DropTypesFirst`] policy that drops messages of type `M`.
+    pub fn drop_first<M: Message>() -> Self {
+        Self::DropTypesFirst(vec![TypeId::of::<M>()])
+    }
+
+    /// Adds the message type `M` to a [`OverflowPolicy::DropTypesFirst`] policy, other policies
+    /// are returned unchanged.
+    pub fn and_drop<M: Message>(mut self) -> Self {
+        if let Self::DropTypesFirst(types) = &mut self {
+            types.push(TypeId::of::<M>());
+        }
+        self
+    }
+}
 
 #[cfg(feature = "bot")]
-use {crate::common::override_field_with_doc, serenity::all::*};
+use serenity::all::*;
 
 /// Configuration settings for the Discord bot.
 ///
@@ -19,6 +75,14 @@ use {crate::common::override_field_with_doc, serenity::all::*};
 /// - Gateway intents
 /// - Online status
 /// - Activity status
+/// - How many events are forwarded to bevy per frame
+/// - Capacity of the event queue and what to do when it is full
+/// - How long to wait for the client to shut down
+/// - Whether the client is restarted when it stops
+/// - Whether unanswered interactions are deferred automatically
+/// - Where the HTTP requests are sent
+/// - Whether the gateway events are written with their context or as payloads
+/// - Whether the gateway events are also written as a single ordered stream
 #[cfg(feature = "bot")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
 #[derive(Default, Resource, Clone, Debug)]
@@ -28,6 +92,18 @@ pub struct DiscordBotConfig {
     pub(crate) status: Option<OnlineStatus>,
     pub(crate) activity: Option<ActivityData>,
     pub(crate) shards: u32,
+    pub(crate) max_messages_per_frame: Option<usize>,
+    pub(crate) max_time_per_frame: Option<Duration>,
+    pub(crate) channel_capacity: Option<usize>,
+    pub(crate) overflow_policy: OverflowPolicy,
+    pub(crate) shutdown_timeout: Option<Duration>,
+    pub(crate) restart_policy: Option<RestartPolicy>,
+    pub(crate) interaction_watchdog: Option<InteractionWatchdog>,
+    pub(crate) base_url: Option<String>,
+    pub(crate) message_mode: BotMessageMode,
+    pub(crate) event_stream: bool,
+    #[cfg(feature = "recording")]
+    pub(crate) record_events: Option<std::path::PathBuf>,
 }
 
 #[cfg(feature = "bot")]
@@ -41,6 +117,265 @@ impl DiscordBotConfig {
     override_field_with_doc!(status, OnlineStatus, "Sets the initial status.");
     override_field_with_doc!(activity, ActivityData, "Sets the initial activity.");
     initialize_field_with_doc!(shards, u32, "The total number of shards to use.");
+    override_field_with_doc!(
+        max_messages_per_frame,
+        usize,
+        "Sets the maximum number of discord events forwarded as bevy messages in a single frame. \
+        Remaining events stay queued, in order, for the next frame. By default all pending events \
+        are forwarded, as with `0`."
+    );
+    override_field_with_doc!(
+        max_time_per_frame,
+        Duration,
+        "Sets the maximum time spent forwarding discord events as bevy messages in a single frame. \
+        Remaining events stay queued, in order, for the next frame. `Duration::ZERO` doesn't \
+        limit the time."
+    );
+    override_field_with_doc!(
+        channel_capacity,
+        usize,
+        "Sets the maximum number of discord events waiting to be forwarded to bevy. \
+        By default the queue is unbounded, as with `0`."
+    );
+    initialize_field_with_doc!(
+        overflow_policy,
+        OverflowPolicy,
+        "Sets what happens to new events once the queue has reached its capacity."
+    );
+    override_field_with_doc!(
+        shutdown_timeout,
+        Duration,
+        "Sets how long the app waits for the shards to close and the pending outbound work \
+        to finish when it exits. Defaults to 5 seconds."
+    );
+    override_field_with_doc!(
+        restart_policy,
+        RestartPolicy,
+        "Restarts the discord client with the given [`RestartPolicy`] whenever it stops. \
+        By default the client isn't restarted."
+    );
+    override_field_with_doc!(
+        interaction_watchdog,
+        InteractionWatchdog,
+        "Watches the interactions with the given [`InteractionWatchdog`], deferring them before \
+        Discord's deadline. By default interactions aren't watched."
+    );
+    override_field_with_doc!(
+        base_url,
+        String,
+        "Sends the HTTP requests to the given URL instead of `https://discord.com`, e.g. to a \
+        proxy or a local stand-in for discord. The requests aren't rate limited by the bot then, \
+        the proxy is expected to do it. The bot still connects to the gateway returned by \
+        `/gateway/bot`."
+    );
+    initialize_field_with_doc!(
+        message_mode,
+        BotMessageMode,
+        "Sets which messages are written for the gateway events, see [`BotMessageMode`]."
+    );
+    initialize_field_with_doc!(
+        event_stream,
+        bool,
+        "Also writes every gateway event as a [`DiscordEvent`](crate::messages::bot::DiscordEvent), \
+        which are read in the order they were queued. Disabled by default."
+    );
+
+    /// Records the gateway events to the file, overwriting it, so that they can be replayed with
+    /// [`DiscordReplayPlugin`](crate::DiscordReplayPlugin).
+    #[cfg(feature = "recording")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "recording")))]
+    pub fn record_events(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.record_events = Some(path.into());
+        self
+    }
+}
+
+/// Decides which messages are written for the gateway events.
+///
+/// The [payload messages](crate::messages::payload) don't carry a [`Context`], only the id of
+/// the shard that received the event. The plugins of this crate that read the gateway events
+/// need the messages with their context, so these are still written with it in
+/// [`Payload`](Self::Payload) mode, followed by their payload:
+/// - [`BotReadyMessage`](crate::messages::bot::BotReadyMessage), always
+/// - [`InteractionCreateMessage`](crate::messages::bot::InteractionCreateMessage), always, for
+///   the routing of commands, autocompletes, components and modals
+/// - [`DiscordMessage`](crate::messages::bot::DiscordMessage), once a prefix command is added
+/// - The guild, role, channel, thread and member messages, with
+///   [`DiscordEntitiesPlugin`](crate::DiscordEntitiesPlugin)
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy_discord::config::{BotMessageMode, DiscordBotConfig};
+///
+/// let config = DiscordBotConfig::default().message_mode(BotMessageMode::Payload);
+/// ```
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotMessageMode {
+    /// Writes the messages of [`messages::bot`](crate::messages::bot), with their context.
+    #[default]
+    Context,
+    /// Writes the messages of [`messages::payload`](crate::messages::payload) instead, except
+    /// for the messages read by the plugins of this crate, which are written both ways.
+    Payload,
+    /// Writes both, the message with its context first.
+    Both,
+}
+
+/// Decides how the discord client is restarted after it has stopped, e.g. because of a network
+/// loss or a Discord outage.
+///
+/// The delay before each attempt starts at `initial_delay` and is multiplied by `multiplier`
+/// after every consecutive failure, up to `max_delay`. The attempts are reset once the client
+/// has connected again. Errors that can't be fixed by retrying, like an invalid token, stop the
+/// client for good.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy_discord::config::{DiscordBotConfig, RestartPolicy};
+/// use std::time::Duration;
+///
+/// let config = DiscordBotConfig::default().restart_policy(
+///     RestartPolicy::default()
+///         .initial_delay(Duration::from_secs(2))
+///         .max_attempts(10),
+/// );
+/// ```
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartPolicy {
+    pub(crate) initial_delay: Duration,
+    pub(crate) max_delay: Duration,
+    pub(crate) multiplier: f64,
+    pub(crate) max_attempts: Option<u32>,
+}
+
+#[cfg(feature = "bot")]
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300),
+            multiplier: 2.0,
+            max_attempts: None,
+        }
+    }
+}
+
+#[cfg(feature = "bot")]
+impl RestartPolicy {
+    initialize_field_with_doc!(
+        initial_delay,
+        Duration,
+        "Sets the delay before the first attempt. Defaults to 1 second."
+    );
+    initialize_field_with_doc!(
+        max_delay,
+        Duration,
+        "Sets the maximum delay between two attempts. Defaults to 5 minutes."
+    );
+
+    /// Sets the factor the delay is multiplied with after every failed attempt. Defaults to 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplier is lower than 1.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        assert!(
+            multiplier >= 1.0,
+            "the multiplier of the restart policy has to be at least 1"
+        );
+        self.multiplier = multiplier;
+        self
+    }
+
+    override_field_with_doc!(
+        max_attempts,
+        u32,
+        "Sets the maximum number of consecutive attempts. By default it retries forever."
+    );
+
+    /// Returns the delay before the given attempt, starting at 1.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+
+        self.initial_delay
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_delay)
+    }
+}
+
+/// Watches the interactions received by the bot, so that they don't fail while bevy is busy.
+///
+/// Discord fails an interaction that isn't answered within 3 seconds. An interaction that hasn't
+/// been answered after `defer_after` is deferred, and the response later sent with
+/// [`DiscordRequest::RespondInteraction`](crate::messages::http::DiscordRequest::RespondInteraction)
+/// becomes a follow-up or an edit of the original response instead. Interactions that still
+/// aren't answered after `expire_after` are reported as
+/// [`UnansweredInteractionMessage`](crate::messages::bot::UnansweredInteractionMessage).
+///
+/// Only the responses sent through [`DiscordRequest`](crate::messages::http::DiscordRequest) are
+/// seen by the watchdog, don't enable it if the interactions are answered in another way.
+/// Autocompletes can't be deferred, and modals can't be opened once the interaction has been
+/// deferred.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy_discord::config::{DiscordBotConfig, InteractionWatchdog};
+/// use std::time::Duration;
+///
+/// let config = DiscordBotConfig::default().interaction_watchdog(
+///     InteractionWatchdog::default()
+///         .defer_after(Duration::from_millis(1500))
+///         .ephemeral(true),
+/// );
+/// ```
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InteractionWatchdog {
+    pub(crate) defer_after: Option<Duration>,
+    pub(crate) expire_after: Duration,
+    pub(crate) ephemeral: bool,
+}
+
+#[cfg(feature = "bot")]
+impl Default for InteractionWatchdog {
+    fn default() -> Self {
+        Self {
+            defer_after: Some(Duration::from_secs(2)),
+            expire_after: Duration::from_secs(15 * 60),
+            ephemeral: false,
+        }
+    }
+}
+
+#[cfg(feature = "bot")]
+impl InteractionWatchdog {
+    /// Sets how long an interaction can stay unanswered before it is deferred, `None` only
+    /// reports the interactions that weren't answered within 3 seconds. Defaults to 2 seconds.
+    pub fn defer_after(mut self, defer_after: impl Into<Option<Duration>>) -> Self {
+        self.defer_after = defer_after.into();
+        self
+    }
+
+    initialize_field_with_doc!(
+        expire_after,
+        Duration,
+        "Sets how long a deferred interaction can stay unanswered before it is reported. \
+        Defaults to 15 minutes, after which Discord doesn't accept the response anymore."
+    );
+    initialize_field_with_doc!(
+        ephemeral,
+        bool,
+        "Sets whether the deferred responses to commands and modals are only visible to the \
+        user. Defaults to `false`."
+    );
 }
 
 /// Configuration settings for Discord Rich Presence integration.
@@ -48,12 +383,18 @@ impl DiscordBotConfig {
 /// This struct allows configuring Rich Presence features including:
 /// - Discord Application ID
 /// - Rich Presence subscriptions
+/// - How many events are forwarded to bevy per frame
+/// - Capacity of the event queue and what to do when it is full
 #[cfg(feature = "rich_presence")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rich_presence")))]
 #[derive(Resource, Clone)]
 pub struct DiscordRichPresenceConfig {
     pub(crate) app: discord_sdk::AppId,
     pub(crate) subscriptions: discord_sdk::Subscriptions,
+    pub(crate) max_messages_per_frame: Option<usize>,
+    pub(crate) max_time_per_frame: Option<Duration>,
+    pub(crate) channel_capacity: Option<usize>,
+    pub(crate) overflow_policy: OverflowPolicy,
 }
 
 #[cfg(feature = "rich_presence")]
@@ -62,6 +403,10 @@ impl std::fmt::Debug for DiscordRichPresenceConfig {
         f.debug_struct("DiscordRichPresenceConfig")
             .field("app", &self.app)
             .field("subscriptions", &self.subscriptions.bits())
+            .field("max_messages_per_frame", &self.max_messages_per_frame)
+            .field("max_time_per_frame", &self.max_time_per_frame)
+            .field("channel_capacity", &self.channel_capacity)
+            .field("overflow_policy", &self.overflow_policy)
             .finish()
     }
 }
@@ -72,6 +417,10 @@ impl Default for DiscordRichPresenceConfig {
         Self {
             app: 0,
             subscriptions: discord_sdk::Subscriptions::all(),
+            max_messages_per_frame: None,
+            max_time_per_frame: None,
+            channel_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
         }
     }
 }
@@ -84,4 +433,29 @@ impl DiscordRichPresenceConfig {
         discord_sdk::Subscriptions,
         "Set the subscription for Rich Presence"
     );
+    override_field_with_doc!(
+        max_messages_per_frame,
+        usize,
+        "Sets the maximum number of Rich Presence events forwarded as bevy messages in a single \
+        frame. Remaining events stay queued, in order, for the next frame. By default all pending \
+        events are forwarded, as with `0`."
+    );
+    override_field_with_doc!(
+        max_time_per_frame,
+        Duration,
+        "Sets the maximum time spent forwarding Rich Presence events as bevy messages in a single \
+        frame. Remaining events stay queued, in order, for the next frame. `Duration::ZERO` \
+        doesn't limit the time."
+    );
+    override_field_with_doc!(
+        channel_capacity,
+        usize,
+        "Sets the maximum number of Rich Presence events waiting to be forwarded to bevy. \
+        By default the queue is unbounded, as with `0`."
+    );
+    initialize_field_with_doc!(
+        overflow_policy,
+        OverflowPolicy,
+        "Sets what happens to new events once the queue has reached its capacity."
+    );
 }
@@ -0,0 +1,696 @@
+//! Mirrors the guilds, channels, roles and members seen by the bot into the ECS.
+//!
+//! Add [`DiscordEntitiesPlugin`] next to [`DiscordBotPlugin`](crate::DiscordBotPlugin) and every
+//! guild is kept as an entity with a [`DiscordGuild`] component, along with its channels
+//! ([`DiscordChannel`]), roles ([`DiscordRole`]) and members ([`DiscordMember`]). The entities are
+//! kept in sync by the create, update and delete messages of the bot, so they can be read with
+//! normal queries instead of reacting to the messages.
+//!
+//! Channels, roles and members are linked to their guild through the [`ChannelOf`], [`RoleOf`]
+//! and [`MemberOf`] relationships, and despawned along with it. A member can have several roles,
+//! which a relationship can't express, so the role entities of a member are listed in the
+//! [`MemberRoles`] component instead. It isn't updated by bevy, but by the plugin when the member
+//! changes or one of its roles is created or deleted.
+//!
+//! When a guild is sent again, e.g. once the bot has reconnected, its channels and roles that
+//! aren't in it anymore are despawned, and so are its members unless only some of them were
+//! sent.
+//!
+//! Members are only known once they join or are requested, unless the `GUILD_MEMBERS` intent is
+//! enabled, and threads only once they are created or updated.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::entities::{DiscordChannel, DiscordEntitiesPlugin, DiscordGuild, GuildChannels};
+//!
+//! fn list_channels(
+//!     guilds: Query<(&DiscordGuild, &GuildChannels), Changed<DiscordGuild>>,
+//!     channels: Query<&DiscordChannel>,
+//! ) {
+//!     for (guild, guild_channels) in &guilds {
+//!         for channel in channels.iter_many(guild_channels.iter()) {
+//!             info!("{}: #{}", guild.0.name, channel.0.name);
+//!         }
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_plugins(DiscordEntitiesPlugin)
+//!     .add_systems(Update, list_channels)
+//!     .run();
+//! ```
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use serenity::all::{
+    ChannelId, GuildChannel, GuildId, GuildMemberUpdateEvent, Member, PartialGuild, Role, RoleId,
+    UserId,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::DiscordSystems;
+use crate::messages::bot::*;
+use crate::messages::{read_with_context, send_events_bot};
+
+/// A plugin that keeps the guilds, channels, roles and members seen by the bot as entities.
+///
+/// It requires [`DiscordBotPlugin`](crate::DiscordBotPlugin), see the [module](self) for more
+/// information.
+pub struct DiscordEntitiesPlugin;
+
+impl Plugin for DiscordEntitiesPlugin {
+    fn build(&self, app: &mut App) {
+        read_with_context::<GuildCreateMessage>(app);
+        read_with_context::<GuildUpdateMessage>(app);
+        read_with_context::<GuildDeleteMessage>(app);
+        read_with_context::<GuildRoleCreateMessage>(app);
+        read_with_context::<GuildRoleUpdateMessage>(app);
+        read_with_context::<GuildRoleDeleteMessage>(app);
+        read_with_context::<ChannelCreateMessage>(app);
+        read_with_context::<ChannelUpdateMessage>(app);
+        read_with_context::<ChannelDeleteMessage>(app);
+        read_with_context::<CategoryCreateMessage>(app);
+        read_with_context::<CategoryDeleteMessage>(app);
+        read_with_context::<ThreadCreateMessage>(app);
+        read_with_context::<ThreadUpdateMessage>(app);
+        read_with_context::<ThreadDeleteMessage>(app);
+        read_with_context::<GuildMemberAdditionMessage>(app);
+        read_with_context::<GuildMemberUpdateMessage>(app);
+        read_with_context::<GuildMemberRemovalMessage>(app);
+        read_with_context::<GuildMembersChunkMessage>(app);
+
+        app.init_resource::<DiscordEntities>().add_systems(
+            Update,
+            (
+                sync_guilds,
+                sync_roles,
+                sync_channels,
+                sync_threads,
+                sync_members,
+            )
+                .chain()
+                .after(send_events_bot)
+                .in_set(DiscordSystems),
+        );
+    }
+}
+
+/// A guild the bot is in.
+#[derive(Component, Debug, Clone)]
+pub struct DiscordGuild(pub PartialGuild);
+
+/// A channel, category or thread of a guild.
+#[derive(Component, Debug, Clone)]
+pub struct DiscordChannel(pub GuildChannel);
+
+/// A role of a guild.
+#[derive(Component, Debug, Clone)]
+pub struct DiscordRole(pub Role);
+
+/// A member of a guild.
+#[derive(Component, Debug, Clone)]
+pub struct DiscordMember(pub Member);
+
+/// Entities of the [`DiscordRole`]s of a [`DiscordMember`].
+///
+/// Unlike [`GuildRoles`], it is a plain component rather than a relationship, since a member can
+/// have several roles and a role several members. Roles that aren't known to the plugin are left
+/// out.
+#[derive(Component, Debug, Clone, Default)]
+pub struct MemberRoles(pub Vec<Entity>);
+
+/// Links a [`DiscordChannel`] to its [`DiscordGuild`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = GuildChannels)]
+pub struct ChannelOf(pub Entity);
+
+/// Entities of the [`DiscordChannel`]s of a [`DiscordGuild`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = ChannelOf, linked_spawn)]
+pub struct GuildChannels(Vec<Entity>);
+
+impl GuildChannels {
+    /// Iterates over the channel entities.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// Links a [`DiscordRole`] to its [`DiscordGuild`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = GuildRoles)]
+pub struct RoleOf(pub Entity);
+
+/// Entities of the [`DiscordRole`]s of a [`DiscordGuild`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = RoleOf, linked_spawn)]
+pub struct GuildRoles(Vec<Entity>);
+
+impl GuildRoles {
+    /// Iterates over the role entities.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// Links a [`DiscordMember`] to its [`DiscordGuild`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = GuildMembers)]
+pub struct MemberOf(pub Entity);
+
+/// Entities of the [`DiscordMember`]s of a [`DiscordGuild`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = MemberOf, linked_spawn)]
+pub struct GuildMembers(Vec<Entity>);
+
+impl GuildMembers {
+    /// Iterates over the member entities.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// Looks up the entities of [`DiscordEntitiesPlugin`] by their discord ids.
+#[derive(Resource, Debug, Default)]
+pub struct DiscordEntities {
+    guilds: HashMap<GuildId, Entity>,
+    channels: HashMap<ChannelId, Entity>,
+    roles: HashMap<RoleId, Entity>,
+    members: HashMap<(GuildId, UserId), Entity>,
+}
+
+impl DiscordEntities {
+    /// Returns the entity of the guild.
+    pub fn guild(&self, id: GuildId) -> Option<Entity> {
+        self.guilds.get(&id).copied()
+    }
+
+    /// Returns the entity of the channel, category or thread.
+    pub fn channel(&self, id: ChannelId) -> Option<Entity> {
+        self.channels.get(&id).copied()
+    }
+
+    /// Returns the entity of the role.
+    pub fn role(&self, id: RoleId) -> Option<Entity> {
+        self.roles.get(&id).copied()
+    }
+
+    /// Returns the entity of the user's membership in the guild.
+    pub fn member(&self, guild_id: GuildId, user_id: UserId) -> Option<Entity> {
+        self.members.get(&(guild_id, user_id)).copied()
+    }
+
+    fn member_roles(&self, member: &Member) -> MemberRoles {
+        MemberRoles(
+            member
+                .roles
+                .iter()
+                .filter_map(|role| self.role(*role))
+                .collect(),
+        )
+    }
+
+    fn upsert_role(&mut self, commands: &mut Commands, role: Role) -> Option<Entity> {
+        let guild = self.guild(role.guild_id)?;
+
+        Some(upsert(
+            commands,
+            &mut self.roles,
+            role.id,
+            (DiscordRole(role), RoleOf(guild)),
+        ))
+    }
+
+    fn upsert_channel(&mut self, commands: &mut Commands, channel: GuildChannel) {
+        let Some(guild) = self.guild(channel.guild_id) else {
+            return;
+        };
+
+        upsert(
+            commands,
+            &mut self.channels,
+            channel.id,
+            (DiscordChannel(channel), ChannelOf(guild)),
+        );
+    }
+
+    fn upsert_member(&mut self, commands: &mut Commands, member: Member) {
+        let Some(guild) = self.guild(member.guild_id) else {
+            return;
+        };
+
+        let key = (member.guild_id, member.user.id);
+        let roles = self.member_roles(&member);
+
+        upsert(
+            commands,
+            &mut self.members,
+            key,
+            (DiscordMember(member), roles, MemberOf(guild)),
+        );
+    }
+}
+
+/// Inserts the bundle on the entity of the key, spawning it if there is none, and returns the
+/// entity.
+fn upsert<K: Hash + Eq>(
+    commands: &mut Commands,
+    entities: &mut HashMap<K, Entity>,
+    key: K,
+    bundle: impl Bundle,
+) -> Entity {
+    match entities.get(&key) {
+        Some(entity) => {
+            commands.entity(*entity).try_insert(bundle);
+            *entity
+        }
+        None => {
+            let entity = commands.spawn(bundle).id();
+            entities.insert(key, entity);
+            entity
+        }
+    }
+}
+
+fn despawn<K: Hash + Eq>(commands: &mut Commands, entities: &mut HashMap<K, Entity>, key: &K) {
+    if let Some(entity) = entities.remove(key) {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+/// Despawns the `children` whose key isn't kept, and returns their entities.
+fn despawn_missing<K: Hash + Eq>(
+    commands: &mut Commands,
+    entities: &mut HashMap<K, Entity>,
+    children: &[Entity],
+    kept: impl Fn(&K) -> bool,
+) -> Vec<Entity> {
+    let mut missing = Vec::new();
+
+    entities.retain(|key, entity| {
+        if !children.contains(entity) || kept(key) {
+            return true;
+        }
+
+        commands.entity(*entity).try_despawn();
+        missing.push(*entity);
+        false
+    });
+
+    missing
+}
+
+/// Returns the channel, role and member entities of a guild.
+fn children(
+    (channels, roles, members): (
+        Option<&GuildChannels>,
+        Option<&GuildRoles>,
+        Option<&GuildMembers>,
+    ),
+) -> (Vec<Entity>, Vec<Entity>, Vec<Entity>) {
+    (
+        channels.into_iter().flat_map(GuildChannels::iter).collect(),
+        roles.into_iter().flat_map(GuildRoles::iter).collect(),
+        members.into_iter().flat_map(GuildMembers::iter).collect(),
+    )
+}
+
+fn sync_guilds(
+    mut guild_create: MessageReader<GuildCreateMessage>,
+    mut guild_update: MessageReader<GuildUpdateMessage>,
+    mut guild_delete: MessageReader<GuildDeleteMessage>,
+    mut entities: ResMut<DiscordEntities>,
+    mut commands: Commands,
+    guilds: Query<(
+        Option<&GuildChannels>,
+        Option<&GuildRoles>,
+        Option<&GuildMembers>,
+    )>,
+    mut member_roles: Query<&mut MemberRoles>,
+) {
+    for event in guild_create.read() {
+        let guild = &event.guild;
+
+        // The guild is sent again when the bot reconnects, without what was deleted meanwhile
+        if let Some((channels, roles, members)) = entities
+            .guild(guild.id)
+            .and_then(|entity| guilds.get(entity).ok())
+            .map(children)
+        {
+            despawn_missing(&mut commands, &mut entities.channels, &channels, |id| {
+                guild.channels.contains_key(id) || guild.threads.iter().any(|t| t.id == *id)
+            });
+
+            let roles = despawn_missing(&mut commands, &mut entities.roles, &roles, |id| {
+                guild.roles.contains_key(id)
+            });
+            for mut member_roles in &mut member_roles {
+                member_roles.0.retain(|role| !roles.contains(role));
+            }
+
+            // Large guilds are sent with only some of their members
+            if guild.members.len() as u64 == guild.member_count {
+                despawn_missing(
+                    &mut commands,
+                    &mut entities.members,
+                    &members,
+                    |(_, user_id)| guild.members.contains_key(user_id),
+                );
+            }
+        }
+
+        upsert(
+            &mut commands,
+            &mut entities.guilds,
+            guild.id,
+            DiscordGuild(PartialGuild::from(guild.clone())),
+        );
+
+        // Roles first, so that the members can be linked to them
+        for role in guild.roles.values() {
+            entities.upsert_role(&mut commands, role.clone());
+        }
+
+        for channel in guild.channels.values().chain(guild.threads.iter()) {
+            entities.upsert_channel(&mut commands, channel.clone());
+        }
+
+        for member in guild.members.values() {
+            entities.upsert_member(&mut commands, member.clone());
+        }
+    }
+
+    for event in guild_update.read() {
+        upsert(
+            &mut commands,
+            &mut entities.guilds,
+            event.new_data.id,
+            DiscordGuild(event.new_data.clone()),
+        );
+    }
+
+    for event in guild_delete.read() {
+        // Unavailable guilds are only affected by an outage, the bot is still in them
+        if event.incomplete.unavailable {
+            continue;
+        }
+
+        let Some(entity) = entities.guilds.remove(&event.incomplete.id) else {
+            continue;
+        };
+
+        // The channels, roles and members are despawned with the guild
+        if let Ok(guild) = guilds.get(entity) {
+            let (channels, roles, members) = children(guild);
+
+            entities.channels.retain(|_, e| !channels.contains(e));
+            entities.roles.retain(|_, e| !roles.contains(e));
+            entities.members.retain(|_, e| !members.contains(e));
+        }
+
+        commands.entity(entity).try_despawn();
+    }
+}
+
+fn sync_roles(
+    mut role_create: MessageReader<GuildRoleCreateMessage>,
+    mut role_update: MessageReader<GuildRoleUpdateMessage>,
+    mut role_delete: MessageReader<GuildRoleDeleteMessage>,
+    mut entities: ResMut<DiscordEntities>,
+    mut commands: Commands,
+    mut members: Query<(&DiscordMember, &mut MemberRoles)>,
+) {
+    let upserted = role_create
+        .read()
+        .map(|event| &event.new)
+        .chain(role_update.read().map(|event| &event.new));
+
+    for role in upserted {
+        let Some(entity) = entities.upsert_role(&mut commands, role.clone()) else {
+            continue;
+        };
+
+        // Members that already have the role, e.g. loaded before it was created
+        for (member, mut roles) in &mut members {
+            if member.0.guild_id == role.guild_id
+                && member.0.roles.contains(&role.id)
+                && !roles.0.contains(&entity)
+            {
+                roles.0.push(entity);
+            }
+        }
+    }
+
+    for event in role_delete.read() {
+        let Some(entity) = entities.role(event.removed_role_id) else {
+            continue;
+        };
+
+        for (_, mut roles) in &mut members {
+            roles.0.retain(|role| *role != entity);
+        }
+
+        despawn(&mut commands, &mut entities.roles, &event.removed_role_id);
+    }
+}
+
+fn sync_channels(
+    mut channel_create: MessageReader<ChannelCreateMessage>,
+    mut channel_update: MessageReader<ChannelUpdateMessage>,
+    mut channel_delete: MessageReader<ChannelDeleteMessage>,
+    mut category_create: MessageReader<CategoryCreateMessage>,
+    mut category_delete: MessageReader<CategoryDeleteMessage>,
+    mut entities: ResMut<DiscordEntities>,
+    mut commands: Commands,
+) {
+    let created = channel_create
+        .read()
+        .map(|event| &event.channel)
+        .chain(channel_update.read().map(|event| &event.new))
+        .chain(category_create.read().map(|event| &event.category));
+
+    for channel in created {
+        entities.upsert_channel(&mut commands, channel.clone());
+    }
+
+    let deleted = channel_delete
+        .read()
+        .map(|event| event.channel.id)
+        .chain(category_delete.read().map(|event| event.category.id));
+
+    for id in deleted {
+        despawn(&mut commands, &mut entities.channels, &id);
+    }
+}
+
+fn sync_threads(
+    mut thread_create: MessageReader<ThreadCreateMessage>,
+    mut thread_update: MessageReader<ThreadUpdateMessage>,
+    mut thread_delete: MessageReader<ThreadDeleteMessage>,
+    mut entities: ResMut<DiscordEntities>,
+    mut commands: Commands,
+) {
+    let created = thread_create
+        .read()
+        .map(|event| &event.thread)
+        .chain(thread_update.read().map(|event| &event.new));
+
+    for thread in created {
+        entities.upsert_channel(&mut commands, thread.clone());
+    }
+
+    for event in thread_delete.read() {
+        despawn(&mut commands, &mut entities.channels, &event.thread.id);
+    }
+}
+
+fn sync_members(
+    mut member_addition: MessageReader<GuildMemberAdditionMessage>,
+    mut member_update: MessageReader<GuildMemberUpdateMessage>,
+    mut member_removal: MessageReader<GuildMemberRemovalMessage>,
+    mut members_chunk: MessageReader<GuildMembersChunkMessage>,
+    mut entities: ResMut<DiscordEntities>,
+    mut commands: Commands,
+    mut members: Query<&mut DiscordMember>,
+) {
+    for event in member_addition.read() {
+        entities.upsert_member(&mut commands, event.new_member.clone());
+    }
+
+    for event in members_chunk.read() {
+        for member in event.chunk.members.values() {
+            entities.upsert_member(&mut commands, member.clone());
+        }
+    }
+
+    for event in member_update.read() {
+        if let Some(member) = &event.new {
+            entities.upsert_member(&mut commands, member.clone());
+            continue;
+        }
+
+        // Without the `bot_cache` feature only the event is available
+        let Some(entity) = entities.member(event.event.guild_id, event.event.user.id) else {
+            continue;
+        };
+
+        if let Ok(mut member) = members.get_mut(entity) {
+            apply_member_update(&mut member.0, &event.event);
+            commands
+                .entity(entity)
+                .try_insert(entities.member_roles(&member.0));
+        }
+    }
+
+    for event in member_removal.read() {
+        despawn(
+            &mut commands,
+            &mut entities.members,
+            &(event.guild_id, event.user.id),
+        );
+    }
+}
+
+fn apply_member_update(member: &mut Member, event: &GuildMemberUpdateEvent) {
+    member.user = event.user.clone();
+    member.nick = event.nick.clone();
+    member.avatar = event.avatar;
+    member.banner = event.banner;
+    member.roles = event.roles.clone();
+    member.joined_at = Some(event.joined_at);
+    member.premium_since = event.premium_since;
+    member.deaf = event.deaf;
+    member.mute = event.mute;
+    member.pending = event.pending;
+    member.communication_disabled_until = event.communication_disabled_until;
+    member.unusual_dm_activity_until = event.unusual_dm_activity_until;
+    member.avatar_decoration_data = event.avatar_decoration_data;
+
+    if let Some(flags) = event.flags {
+        member.flags = flags;
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::{DiscordTestHarness, DiscordTestPlugin, FakeGuild, FakeUser};
+    use serenity::all::{Guild, Permissions};
+
+    fn app() -> (App, DiscordTestHarness) {
+        let mut app = App::new();
+        app.add_plugins(DiscordTestPlugin::default())
+            .add_plugins(DiscordEntitiesPlugin);
+        let harness = app.world().resource::<DiscordTestHarness>().clone();
+        (app, harness)
+    }
+
+    fn create_guild(app: &mut App, harness: &DiscordTestHarness, guild: Guild) {
+        harness.send(GuildCreateMessage {
+            ctx: harness.ctx(),
+            guild,
+            is_new: None,
+        });
+        app.update();
+    }
+
+    fn entities(app: &App) -> &DiscordEntities {
+        app.world().resource::<DiscordEntities>()
+    }
+
+    fn member_roles(app: &App, user_id: u64) -> Vec<Entity> {
+        let member = entities(app)
+            .member(GuildId::new(1), UserId::new(user_id))
+            .unwrap();
+        app.world().get::<MemberRoles>(member).unwrap().0.clone()
+    }
+
+    #[test]
+    fn guilds_are_linked_to_their_children() {
+        let (mut app, harness) = app();
+        let guild = FakeGuild::new(1)
+            .text_channel(10, "general")
+            .role(5, "admin", Permissions::ADMINISTRATOR)
+            .member(FakeUser::new(20).build(), [RoleId::new(5)])
+            .build();
+        create_guild(&mut app, &harness, guild);
+
+        let entities = entities(&app);
+        let guild = entities.guild(GuildId::new(1)).unwrap();
+        let channel = entities.channel(ChannelId::new(10)).unwrap();
+        let role = entities.role(RoleId::new(5)).unwrap();
+        let member = entities.member(GuildId::new(1), UserId::new(20)).unwrap();
+
+        let world = app.world();
+        assert_eq!(world.get::<ChannelOf>(channel), Some(&ChannelOf(guild)));
+        assert_eq!(world.get::<RoleOf>(role), Some(&RoleOf(guild)));
+        assert_eq!(world.get::<MemberOf>(member), Some(&MemberOf(guild)));
+        assert_eq!(member_roles(&app, 20), [role]);
+    }
+
+    #[test]
+    fn roles_created_after_their_members_are_linked() {
+        let (mut app, harness) = app();
+        let guild = FakeGuild::new(1)
+            .member(FakeUser::new(20).build(), [RoleId::new(5)])
+            .build();
+        create_guild(&mut app, &harness, guild);
+        assert!(member_roles(&app, 20).is_empty());
+
+        let mut role = Role::default();
+        role.id = RoleId::new(5);
+        role.guild_id = GuildId::new(1);
+        harness.send(GuildRoleCreateMessage {
+            ctx: harness.ctx(),
+            new: role,
+        });
+        app.update();
+
+        let role = entities(&app).role(RoleId::new(5)).unwrap();
+        assert_eq!(member_roles(&app, 20), [role]);
+    }
+
+    #[test]
+    fn children_missing_from_a_new_guild_create_are_despawned() {
+        let (mut app, harness) = app();
+        let guild = FakeGuild::new(1)
+            .text_channel(10, "general")
+            .text_channel(11, "old")
+            .role(5, "admin", Permissions::ADMINISTRATOR)
+            .member(FakeUser::new(20).build(), [RoleId::new(5)])
+            .member(FakeUser::new(21).build(), []);
+        create_guild(&mut app, &harness, guild.build());
+
+        let old_channel = entities(&app).channel(ChannelId::new(11)).unwrap();
+        let role = entities(&app).role(RoleId::new(5)).unwrap();
+        let left = entities(&app)
+            .member(GuildId::new(1), UserId::new(21))
+            .unwrap();
+
+        // After a reconnect
+        let guild = FakeGuild::new(1)
+            .text_channel(10, "general")
+            .member(FakeUser::new(20).build(), [])
+            .build();
+        create_guild(&mut app, &harness, guild);
+
+        let entities = entities(&app);
+        assert!(entities.channel(ChannelId::new(10)).is_some());
+        assert!(entities.channel(ChannelId::new(11)).is_none());
+        assert!(entities.role(RoleId::new(5)).is_none());
+        assert!(entities.member(GuildId::new(1), UserId::new(21)).is_none());
+        for entity in [old_channel, role, left] {
+            assert!(app.world().get_entity(entity).is_err());
+        }
+        assert!(member_roles(&app, 20).is_empty());
+
+        let guild = entities.guild(GuildId::new(1)).unwrap();
+        assert_eq!(app.world().get::<GuildChannels>(guild).unwrap().0.len(), 1);
+    }
+}
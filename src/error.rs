@@ -0,0 +1,212 @@
+//! Error types reported by the Discord integration.
+//!
+//! Instead of panicking, failures of the discord clients are turned into these errors and
+//! dispatched as bevy messages, so that the app keeps running without Discord:
+//!
+//! - [`DiscordBotError`] through [`DiscordBotErrorMessage`](crate::messages::bot::DiscordBotErrorMessage)
+//!   (available with `bot` feature)
+//! - [`DiscordHttpError`] through [`DiscordRequestCompleted`](crate::messages::http::DiscordRequestCompleted)
+//!   (available with `http` feature)
+//! - [`RichPresenceError`] through [`RichPresenceErrorMessage`](crate::messages::rich_presence::RichPresenceErrorMessage)
+//!   (available with `rich_presence` feature)
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Errors that stop the discord bot client.
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+#[derive(Debug, Clone)]
+pub enum DiscordBotError {
+    /// The bot token was rejected by Discord.
+    InvalidToken,
+    /// Privileged gateway intents were requested without enabling them on the bot application
+    /// page.
+    DisallowedIntents,
+    /// Undocumented gateway intents were requested.
+    InvalidIntents,
+    /// The gateway connection was closed.
+    GatewayClosed {
+        /// Close code sent by Discord, if any.
+        code: Option<u16>,
+        /// Close reason sent by Discord, empty if none was given.
+        reason: String,
+    },
+    /// Any other error returned by serenity.
+    Other(Arc<serenity::Error>),
+}
+
+#[cfg(feature = "bot")]
+impl DiscordBotError {
+    /// Returns `false` for errors that restarting the client can't fix, like an invalid token.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(
+            self,
+            Self::InvalidToken | Self::DisallowedIntents | Self::InvalidIntents
+        )
+    }
+}
+
+#[cfg(feature = "bot")]
+impl From<serenity::Error> for DiscordBotError {
+    fn from(error: serenity::Error) -> Self {
+        use serenity::all::{GatewayError, HttpError};
+
+        match error {
+            serenity::Error::Gateway(GatewayError::InvalidAuthentication)
+            | serenity::Error::Gateway(GatewayError::NoAuthentication) => Self::InvalidToken,
+            serenity::Error::Http(HttpError::UnsuccessfulRequest(ref response))
+                if response.status_code.as_u16() == 401 =>
+            {
+                Self::InvalidToken
+            }
+            serenity::Error::Gateway(GatewayError::DisallowedGatewayIntents) => {
+                Self::DisallowedIntents
+            }
+            serenity::Error::Gateway(GatewayError::InvalidGatewayIntents) => Self::InvalidIntents,
+            serenity::Error::Gateway(GatewayError::Closed(frame)) => Self::GatewayClosed {
+                code: frame.as_ref().map(|frame| u16::from(frame.code)),
+                reason: frame
+                    .map(|frame| frame.reason.into_owned())
+                    .unwrap_or_default(),
+            },
+            error => Self::Other(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "bot")]
+impl fmt::Display for DiscordBotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidToken => f.write_str("the bot token was rejected by Discord"),
+            Self::DisallowedIntents => f.write_str(
+                "privileged gateway intents were requested without being enabled for the bot",
+            ),
+            Self::InvalidIntents => f.write_str("invalid gateway intents were requested"),
+            Self::GatewayClosed { code, reason } => match code {
+                Some(code) => write!(f, "the gateway connection was closed ({code}): {reason}"),
+                None => f.write_str("the gateway connection was closed"),
+            },
+            Self::Other(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "bot")]
+impl std::error::Error for DiscordBotError {}
+
+/// Errors returned by Discord for a [`DiscordRequest`](crate::messages::http::DiscordRequest).
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+#[derive(Debug, Clone)]
+pub enum DiscordHttpError {
+    /// The bot token was rejected by Discord.
+    Unauthorized,
+    /// The bot is missing the permissions for the request.
+    Forbidden,
+    /// The channel, message or interaction doesn't exist.
+    NotFound,
+    /// The request was rate limited.
+    RateLimited,
+    /// Discord rejected the request for any other reason.
+    Rejected {
+        /// HTTP status code of the response.
+        status: u16,
+        /// [JSON error code](https://discord.com/developers/docs/topics/opcodes-and-status-codes#json)
+        /// sent by Discord.
+        code: isize,
+        /// Error message sent by Discord.
+        message: String,
+    },
+    /// Any other error returned by serenity, e.g. the request couldn't be sent.
+    Other(Arc<serenity::Error>),
+}
+
+#[cfg(feature = "http")]
+impl From<serenity::Error> for DiscordHttpError {
+    fn from(error: serenity::Error) -> Self {
+        use serenity::all::HttpError;
+
+        match error {
+            serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+                match response.status_code.as_u16() {
+                    401 => Self::Unauthorized,
+                    403 => Self::Forbidden,
+                    404 => Self::NotFound,
+                    429 => Self::RateLimited,
+                    status => Self::Rejected {
+                        status,
+                        code: response.error.code,
+                        message: response.error.message,
+                    },
+                }
+            }
+            error => Self::Other(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl fmt::Display for DiscordHttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthorized => f.write_str("the bot token was rejected by Discord"),
+            Self::Forbidden => f.write_str("the bot is missing the permissions for the request"),
+            Self::NotFound => f.write_str("the requested resource doesn't exist"),
+            Self::RateLimited => f.write_str("the request was rate limited"),
+            Self::Rejected {
+                status,
+                code,
+                message,
+            } => write!(
+                f,
+                "discord rejected the request ({status}, {code}): {message}"
+            ),
+            Self::Other(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl std::error::Error for DiscordHttpError {}
+
+/// Errors reported by the Discord Rich Presence client.
+#[cfg(feature = "rich_presence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rich_presence")))]
+#[derive(Debug, Clone)]
+pub enum RichPresenceError {
+    /// The local Discord client couldn't be reached over IPC, usually because it isn't running.
+    IpcUnavailable(Arc<discord_sdk::Error>),
+    /// The local Discord client closed the connection.
+    ConnectionClosed(String),
+    /// Any other error returned by `discord-sdk`.
+    Other(Arc<discord_sdk::Error>),
+}
+
+#[cfg(feature = "rich_presence")]
+impl From<discord_sdk::Error> for RichPresenceError {
+    fn from(error: discord_sdk::Error) -> Self {
+        match error {
+            discord_sdk::Error::NoConnection | discord_sdk::Error::Io { .. } => {
+                Self::IpcUnavailable(Arc::new(error))
+            }
+            discord_sdk::Error::Close(reason) => Self::ConnectionClosed(reason),
+            error => Self::Other(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "rich_presence")]
+impl fmt::Display for RichPresenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IpcUnavailable(error) => write!(f, "discord is unavailable: {error}"),
+            Self::ConnectionClosed(reason) => write!(f, "discord closed the connection: {reason}"),
+            Self::Other(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "rich_presence")]
+impl std::error::Error for RichPresenceError {}
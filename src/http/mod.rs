@@ -15,21 +15,61 @@
 //!     .run();
 //! ```
 
-use crate::res::DiscordHttpResource;
+use crate::res::{DiscordHttpBaseUrl, DiscordHttpResource};
 use bevy_app::{App, Plugin};
-use serenity::http::Http;
+use serenity::http::{Http, HttpBuilder};
 use std::sync::Arc;
 
+pub(crate) mod request;
+
+use crate::runtime::PendingRequests;
+use request::{CompletedRequests, DiscordRequestPlugin};
+
 /// A Bevy plugin that provides Discord HTTP functionality.
 ///
 /// This plugin initializes a Discord HTTP client with the provided bot token
 /// and makes it available throughout the application as a Bevy resource.
+/// It also executes every [`DiscordRequest`](crate::messages::http::DiscordRequest) written by the app.
+///
+/// The requests are sent to the URL of [`DiscordHttpBaseUrl`] if it is inserted before the
+/// plugin is added.
 pub struct DiscordHttpPlugin(pub String);
 
+impl DiscordHttpPlugin {
+    /// Creates the plugin with the bot token, same as `DiscordHttpPlugin(token)`.
+    pub fn new(token: String) -> Self {
+        Self(token)
+    }
+}
+
+/// Builds the HTTP client, which sends the requests to `base_url` when it is set.
+pub(crate) fn discord_http(token: &str, base_url: Option<&str>) -> Http {
+    let builder = HttpBuilder::new(token);
+
+    match base_url {
+        // Serenity's ratelimiter always sends to discord, the proxy is expected to rate limit
+        Some(base_url) => builder.proxy(base_url).ratelimiter_disabled(true).build(),
+        None => builder.build(),
+    }
+}
+
 impl Plugin for DiscordHttpPlugin {
     fn build(&self, app: &mut App) {
-        let http: Arc<Http> = Arc::new(Http::new(&self.0));
+        let base_url = app
+            .world()
+            .get_resource::<DiscordHttpBaseUrl>()
+            .map(|base_url| base_url.0.as_str());
+        let http: Arc<Http> = Arc::new(discord_http(&self.0, base_url));
+
+        if !app.is_plugin_added::<DiscordRequestPlugin>() {
+            app.add_plugins(DiscordRequestPlugin);
+        }
 
-        app.insert_resource(DiscordHttpResource::new(http));
+        let world = app.world();
+        let http = world.resource::<CompletedRequests>().connect(
+            world.resource::<PendingRequests>(),
+            DiscordHttpResource::new(http),
+        );
+        app.insert_resource(http);
     }
 }
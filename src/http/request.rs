@@ -0,0 +1,207 @@
+use bevy_app::{App, Plugin, PostUpdate, Update};
+use bevy_ecs::prelude::*;
+use flume::{Receiver, Sender};
+use serenity::all::Builder;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::error;
+
+use crate::DiscordSystems;
+#[cfg(feature = "bot")]
+use crate::bot::watchdog::{self, Claim};
+use crate::error::DiscordHttpError;
+use crate::messages::http::{
+    DiscordRequest, DiscordRequestCompleted, DiscordRequestId, DiscordResponse,
+};
+use crate::res::DiscordHttpResource;
+use crate::runtime::PendingRequests;
+use crate::task::{FinishedTasks, trigger_finished_tasks};
+
+/// Registers [`DiscordRequest`] and [`DiscordRequestCompleted`] and the systems that handle them
+/// and [`DiscordTask`](crate::task::DiscordTask)s, added by both `DiscordHttpPlugin` and
+/// `DiscordBotPlugin`.
+pub(crate) struct DiscordRequestPlugin;
+
+impl Plugin for DiscordRequestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<DiscordRequest>()
+            .add_message::<DiscordRequestCompleted>()
+            .init_resource::<CompletedRequests>()
+            .init_resource::<PendingRequests>()
+            .init_resource::<FinishedTasks>()
+            .add_systems(
+                Update,
+                (send_completed_requests, trigger_finished_tasks).in_set(DiscordSystems),
+            )
+            .add_systems(PostUpdate, execute_discord_requests.in_set(DiscordSystems));
+    }
+}
+
+/// Carries the results of [`spawn_request`] back to the app that sent the requests.
+#[derive(Resource)]
+pub(crate) struct CompletedRequests {
+    tx: Sender<DiscordRequestCompleted>,
+    rx: Receiver<DiscordRequestCompleted>,
+}
+
+impl Default for CompletedRequests {
+    fn default() -> Self {
+        let (tx, rx) = flume::unbounded();
+        Self { tx, rx }
+    }
+}
+
+impl CompletedRequests {
+    /// Connects the client to the app, so that the results of its requests are dispatched and
+    /// its requests are counted in the [`PendingRequests`] of the app.
+    pub(crate) fn connect(
+        &self,
+        pending: &PendingRequests,
+        http: DiscordHttpResource,
+    ) -> DiscordHttpResource {
+        DiscordHttpResource {
+            completed: Some(self.tx.clone()),
+            pending: pending.clone(),
+            ..http
+        }
+    }
+}
+
+/// Spawns the request, and reports its result as [`DiscordRequestCompleted`] if the client is
+/// connected to an app, or only logs its failure otherwise.
+pub(crate) fn spawn_request(
+    http: DiscordHttpResource,
+    request: DiscordRequest,
+) -> DiscordRequestId {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    let id = DiscordRequestId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+
+    http.pending.clone().spawn(async move {
+        let result = execute(&http, request)
+            .await
+            .map_err(DiscordHttpError::from);
+
+        match http.completed {
+            // The receiver is only dropped with the app, the result isn't needed anymore then
+            Some(completed) => {
+                let _ = completed
+                    .send_async(DiscordRequestCompleted { id, result })
+                    .await;
+            }
+            None => {
+                if let Err(err) = result {
+                    error!("Discord request failed: {}", err);
+                }
+            }
+        }
+    });
+
+    id
+}
+
+/// Forwards the results of the finished requests as [`DiscordRequestCompleted`].
+fn send_completed_requests(
+    channel: Res<CompletedRequests>,
+    pending: Res<PendingRequests>,
+    http: Option<ResMut<DiscordHttpResource>>,
+    mut completed: MessageWriter<DiscordRequestCompleted>,
+) {
+    // Connects a client inserted by the app itself, requests sent before are only logged
+    if let Some(mut http) = http
+        && http.completed.is_none()
+    {
+        let http = http.bypass_change_detection();
+        *http = channel.connect(&pending, http.clone());
+    }
+
+    completed.write_batch(channel.rx.try_iter());
+}
+
+/// Spawns every [`DiscordRequest`], keeping them until [`DiscordHttpResource`] is available.
+fn execute_discord_requests(
+    mut requests: MessageReader<DiscordRequest>,
+    mut pending: Local<Vec<DiscordRequest>>,
+    http: Option<Res<DiscordHttpResource>>,
+) {
+    pending.extend(requests.read().cloned());
+
+    let Some(http) = http else {
+        return;
+    };
+
+    for request in pending.drain(..) {
+        let http = http.clone();
+
+        http.pending.clone().spawn(async move {
+            if let Err(err) = execute(&http, request).await {
+                error!("Discord request failed: {}", err);
+            }
+        });
+    }
+}
+
+async fn execute(
+    resource: &DiscordHttpResource,
+    request: DiscordRequest,
+) -> serenity::Result<DiscordResponse> {
+    let http = &resource.http;
+    let response = match request {
+        DiscordRequest::SendMessage {
+            channel_id,
+            message,
+        } => DiscordResponse::Message(Box::new(
+            channel_id.send_message(http.as_ref(), message).await?,
+        )),
+        DiscordRequest::EditMessage {
+            channel_id,
+            message_id,
+            message,
+        } => DiscordResponse::Message(Box::new(
+            channel_id.edit_message(http, message_id, message).await?,
+        )),
+        DiscordRequest::AddReaction {
+            channel_id,
+            message_id,
+            reaction,
+        } => {
+            channel_id
+                .create_reaction(http, message_id, reaction)
+                .await?;
+            DiscordResponse::Empty
+        }
+        DiscordRequest::CreateThread {
+            channel_id,
+            message_id: Some(message_id),
+            thread,
+        } => DiscordResponse::Thread(Box::new(
+            channel_id
+                .create_thread_from_message(http, message_id, thread)
+                .await?,
+        )),
+        DiscordRequest::CreateThread {
+            channel_id,
+            message_id: None,
+            thread,
+        } => DiscordResponse::Thread(Box::new(channel_id.create_thread(http, thread).await?)),
+        DiscordRequest::RespondInteraction {
+            interaction_id,
+            token,
+            response,
+        } => {
+            #[cfg(feature = "bot")]
+            if let Some(watched) = &resource.watched
+                && let Claim::Deferred(kind) = watched.claim(interaction_id).await
+            {
+                watchdog::respond_deferred(http, &token, kind, response).await?;
+                return Ok(DiscordResponse::Empty);
+            }
+
+            response
+                .execute(http.as_ref(), (interaction_id, &token))
+                .await?;
+            DiscordResponse::Empty
+        }
+    };
+
+    Ok(response)
+}
@@ -10,6 +10,17 @@ mod bot;
 #[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
 pub use bot::DiscordBotPlugin;
 
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod checks;
+
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod entities;
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub use entities::DiscordEntitiesPlugin;
+
 #[cfg(any(feature = "bot", feature = "rich_presence"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "bot", feature = "rich_presence"))))]
 pub mod config;
@@ -19,12 +30,50 @@ pub mod res;
 
 mod common;
 
+#[cfg(any(feature = "bot", feature = "http", feature = "rich_presence"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bot", feature = "http", feature = "rich_presence")))
+)]
+pub mod error;
+
 #[cfg(feature = "http")]
 mod http;
 #[cfg(feature = "http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
 pub use http::DiscordHttpPlugin;
 
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod message_components;
+
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod modals;
+
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod prefix_commands;
+
+#[cfg(feature = "recording")]
+#[cfg_attr(docsrs, doc(cfg(feature = "recording")))]
+pub mod recording;
+#[cfg(feature = "recording")]
+#[cfg_attr(docsrs, doc(cfg(feature = "recording")))]
+pub use recording::DiscordReplayPlugin;
+
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod slash_commands;
+
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub mod task;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
 #[cfg(feature = "rich_presence")]
 mod rich_presence;
 #[cfg(feature = "rich_presence")]
@@ -36,9 +85,16 @@ pub mod runtime;
 
 #[cfg(any(feature = "bot", feature = "rich_presence"))]
 pub(crate) mod channel;
+#[cfg(any(feature = "bot", feature = "http", feature = "rich_presence"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bot", feature = "http", feature = "rich_presence")))
+)]
+pub mod messages;
+
 #[cfg(any(feature = "bot", feature = "rich_presence"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "bot", feature = "rich_presence"))))]
-pub mod messages;
+pub mod state;
 
 #[cfg(feature = "http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
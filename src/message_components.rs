@@ -0,0 +1,384 @@
+//! Routing of message components, i.e. buttons and select menus, by their `custom_id`.
+//!
+//! Insert a [`ComponentHandler`] on the entity that owns a message, e.g. a poll, with the
+//! patterns of the `custom_id`s of its components, and observe [`ComponentInteracted`] on it.
+//! Patterns are either templates such as `vote:{poll_id}:{option}`, whose parameters are parsed
+//! from the `custom_id`, or prefixes such as `shop:*`.
+//!
+//! Handlers that aren't bound to an entity are registered with
+//! [`DiscordComponentAppExt::add_discord_component_system`] or
+//! [`DiscordComponentAppExt::add_discord_component_observer`]. Components without a handler
+//! are reported as [`UnhandledComponentMessage`](crate::messages::bot::UnhandledComponentMessage).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::message_components::{ComponentHandler, ComponentInteracted};
+//! use bevy_discord::messages::http::DiscordRequest;
+//! use bevy_discord::serenity::all::CreateInteractionResponse;
+//!
+//! #[derive(Component)]
+//! struct Poll {
+//!     votes: Vec<u32>,
+//! }
+//!
+//! fn spawn_poll(mut commands: Commands) {
+//!     commands
+//!         .spawn((
+//!             Poll { votes: vec![0; 2] },
+//!             ComponentHandler::new("vote:42:{option}"),
+//!         ))
+//!         .observe(
+//!             |event: On<ComponentInteracted>,
+//!              mut polls: Query<&mut Poll>,
+//!              mut requests: MessageWriter<DiscordRequest>| {
+//!                 let Ok(mut poll) = polls.get_mut(event.entity) else {
+//!                     return;
+//!                 };
+//!                 if let Some(vote) = event
+//!                     .param("option")
+//!                     .and_then(|option| option.parse::<usize>().ok())
+//!                     .and_then(|option| poll.votes.get_mut(option))
+//!                 {
+//!                     *vote += 1;
+//!                 }
+//!                 requests.write(event.respond(CreateInteractionResponse::Acknowledge));
+//!             },
+//!         );
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_systems(Startup, spawn_poll)
+//!     .run();
+//! ```
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::{IntoObserverSystem, SystemId};
+use serenity::all::{ComponentInteraction, CreateInteractionResponse, Interaction, User};
+use std::collections::HashMap;
+
+use crate::DiscordSystems;
+use crate::messages::bot::{InteractionCreateMessage, UnhandledComponentMessage};
+use crate::messages::http::DiscordRequest;
+use crate::messages::{read_with_context, send_events_bot};
+use crate::modals::{DiscordModal, open_modal};
+
+/// Pattern a `custom_id` is matched against.
+///
+/// - `{name}` is a parameter, it matches anything up to the text that follows it
+/// - A trailing `*` matches the rest of the `custom_id`, making the pattern a prefix
+/// - Everything else has to match exactly
+///
+/// `vote:{poll_id}:{option}` matches `vote:42:1`, with `poll_id` being `42` and `option` `1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomIdPattern {
+    segments: Vec<Segment>,
+    prefix: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+impl CustomIdPattern {
+    /// Parses the pattern.
+    pub fn new(pattern: &str) -> Self {
+        let (pattern, prefix) = match pattern.strip_suffix('*') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            segments.push(Segment::Param(rest[start + 1..start + end].to_string()));
+            rest = &rest[start + end + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Self { segments, prefix }
+    }
+
+    /// Returns the parameters parsed from the `custom_id`, or `None` if it doesn't match.
+    pub fn matches(&self, custom_id: &str) -> Option<HashMap<String, String>> {
+        let mut params = HashMap::new();
+        let mut rest = custom_id;
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Literal(literal) => rest = rest.strip_prefix(literal.as_str())?,
+                Segment::Param(name) => {
+                    let end = match self.segments.get(i + 1) {
+                        Some(Segment::Literal(next)) => rest.find(next.as_str())?,
+                        // Two parameters in a row can't be told apart, the first one is empty
+                        Some(Segment::Param(_)) => 0,
+                        None => rest.len(),
+                    };
+
+                    params.insert(name.clone(), rest[..end].to_string());
+                    rest = &rest[end..];
+                }
+            }
+        }
+
+        (self.prefix || rest.is_empty()).then_some(params)
+    }
+}
+
+impl From<&str> for CustomIdPattern {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl From<String> for CustomIdPattern {
+    fn from(pattern: String) -> Self {
+        Self::new(&pattern)
+    }
+}
+
+/// Routes the components whose `custom_id` matches one of the patterns to the entity, as
+/// [`ComponentInteracted`].
+#[derive(Component, Debug, Clone)]
+pub struct ComponentHandler {
+    patterns: Vec<CustomIdPattern>,
+}
+
+impl ComponentHandler {
+    pub fn new(pattern: impl Into<CustomIdPattern>) -> Self {
+        Self {
+            patterns: vec![pattern.into()],
+        }
+    }
+
+    /// Also routes the components matching the pattern to the entity.
+    pub fn with_pattern(mut self, pattern: impl Into<CustomIdPattern>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Returns the parameters of the first pattern matching the `custom_id`.
+    pub fn matches(&self, custom_id: &str) -> Option<HashMap<String, String>> {
+        self.patterns
+            .iter()
+            .find_map(|pattern| pattern.matches(custom_id))
+    }
+}
+
+/// Triggered on every entity whose [`ComponentHandler`] matches the `custom_id` of a button or
+/// select menu that was used.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ComponentInteracted {
+    /// Entity of the [`ComponentHandler`].
+    pub entity: Entity,
+    pub custom_id: String,
+    /// Parameters parsed from the `custom_id`.
+    pub params: HashMap<String, String>,
+    pub interaction: ComponentInteraction,
+    /// User that used the component.
+    pub user: User,
+}
+
+impl ComponentInteracted {
+    /// Returns the value of the parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// Returns the request responding to the interaction, write it as a message.
+    pub fn respond(&self, response: CreateInteractionResponse) -> DiscordRequest {
+        DiscordRequest::RespondInteraction {
+            interaction_id: self.interaction.id,
+            token: self.interaction.token.clone(),
+            response,
+        }
+    }
+
+    /// Returns the request opening the modal in response to the interaction.
+    pub fn open_modal<T: DiscordModal>(&self) -> DiscordRequest {
+        open_modal::<T>(self.interaction.id, &self.interaction.token)
+    }
+}
+
+/// Systems registered with [`DiscordComponentAppExt::add_discord_component_system`].
+#[derive(Component, Default)]
+struct ComponentSystems(Vec<SystemId<In<ComponentInteracted>>>);
+
+/// Extension trait for [`App`] to handle message components that aren't bound to an entity, see
+/// the [module](self) for more information.
+pub trait DiscordComponentAppExt {
+    /// Runs the system whenever a component matching the pattern is used.
+    fn add_discord_component_system<M>(
+        &mut self,
+        pattern: impl Into<CustomIdPattern>,
+        system: impl IntoSystem<In<ComponentInteracted>, (), M> + 'static,
+    ) -> &mut Self;
+
+    /// Runs the observer whenever a component matching the pattern is used.
+    fn add_discord_component_observer<B: Bundle, M>(
+        &mut self,
+        pattern: impl Into<CustomIdPattern>,
+        observer: impl IntoObserverSystem<ComponentInteracted, B, M>,
+    ) -> &mut Self;
+}
+
+impl DiscordComponentAppExt for App {
+    fn add_discord_component_system<M>(
+        &mut self,
+        pattern: impl Into<CustomIdPattern>,
+        system: impl IntoSystem<In<ComponentInteracted>, (), M> + 'static,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        let system = world.register_system(system);
+        world.spawn((
+            ComponentHandler::new(pattern),
+            ComponentSystems(vec![system]),
+        ));
+        self
+    }
+
+    fn add_discord_component_observer<B: Bundle, M>(
+        &mut self,
+        pattern: impl Into<CustomIdPattern>,
+        observer: impl IntoObserverSystem<ComponentInteracted, B, M>,
+    ) -> &mut Self {
+        self.world_mut()
+            .spawn(ComponentHandler::new(pattern))
+            .observe(observer);
+        self
+    }
+}
+
+/// Routes the used components to their [`ComponentHandler`]s, added by
+/// [`DiscordBotPlugin`](crate::DiscordBotPlugin).
+pub(crate) struct ComponentRoutingPlugin;
+
+impl Plugin for ComponentRoutingPlugin {
+    fn build(&self, app: &mut App) {
+        read_with_context::<InteractionCreateMessage>(app);
+
+        app.add_message::<UnhandledComponentMessage>().add_systems(
+            Update,
+            route_components
+                .after(send_events_bot)
+                .in_set(DiscordSystems),
+        );
+    }
+}
+
+fn route_components(
+    mut interactions: MessageReader<InteractionCreateMessage>,
+    mut unhandled: MessageWriter<UnhandledComponentMessage>,
+    handlers: Query<(Entity, &ComponentHandler, Option<&ComponentSystems>)>,
+    mut commands: Commands,
+) {
+    for event in interactions.read() {
+        let Interaction::Component(interaction) = &event.interaction else {
+            continue;
+        };
+
+        let custom_id = &interaction.data.custom_id;
+        let mut handled = false;
+
+        for (entity, handler, systems) in &handlers {
+            let Some(params) = handler.matches(custom_id) else {
+                continue;
+            };
+
+            let interacted = ComponentInteracted {
+                entity,
+                custom_id: custom_id.clone(),
+                params,
+                interaction: interaction.clone(),
+                user: interaction.user.clone(),
+            };
+
+            for system in systems.into_iter().flat_map(|systems| &systems.0) {
+                commands.run_system_with(*system, interacted.clone());
+            }
+
+            commands.trigger(interacted);
+            handled = true;
+        }
+
+        if !handled {
+            unhandled.write(UnhandledComponentMessage {
+                custom_id: custom_id.clone(),
+                interaction: interaction.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pattern: &str, custom_id: &str) -> Option<Vec<(String, String)>> {
+        let mut params: Vec<_> = CustomIdPattern::new(pattern)
+            .matches(custom_id)?
+            .into_iter()
+            .collect();
+        params.sort();
+        Some(params)
+    }
+
+    fn param(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn literals_match_exactly() {
+        assert_eq!(params("confirm", "confirm"), Some(Vec::new()));
+        assert_eq!(params("confirm", "confirm2"), None);
+        assert_eq!(params("confirm", "cancel"), None);
+    }
+
+    #[test]
+    fn parameters_are_parsed() {
+        assert_eq!(
+            params("vote:{poll_id}:{option}", "vote:42:1"),
+            Some(vec![param("option", "1"), param("poll_id", "42")])
+        );
+        // A parameter ends at the first occurrence of the text that follows it
+        assert_eq!(
+            params("{a}:{b}", "x:y:z"),
+            Some(vec![param("a", "x"), param("b", "y:z")])
+        );
+        assert_eq!(params("vote:{poll_id}:{option}", "vote:42"), None);
+    }
+
+    #[test]
+    fn trailing_star_matches_a_prefix() {
+        assert_eq!(params("menu:*", "menu:a:b"), Some(Vec::new()));
+        assert_eq!(
+            params("page:{n}:*", "page:3:next"),
+            Some(vec![param("n", "3")])
+        );
+        assert_eq!(params("menu:*", "other"), None);
+    }
+
+    #[test]
+    fn unclosed_braces_are_literals() {
+        assert_eq!(params("a{b", "a{b"), Some(Vec::new()));
+        assert_eq!(params("a{b", "ab"), None);
+    }
+}
@@ -3,19 +3,161 @@
 //! This module is split into two main feature-gated submodules:
 //!
 //! - [`bot`] - Events related to Discord bot functionality (requires `bot` feature)
+//! - [`http`] - Requests sent to Discord through its HTTP API (requires `http` feature)
 //! - [`rich_presence`] - Events related to Discord Rich Presence integration (requires `rich_presence` feature)
 
+#[cfg(any(feature = "bot", feature = "rich_presence"))]
 use crate::common::create_message_collection_and_handler;
 
+/// Calls the macro with the gateway events, as the messages of [`bot`] they are written as and
+/// their fields without the [`Context`](serenity::all::Context).
+///
+/// The stage updates of the shards aren't part of them, as they don't come from discord.
+#[cfg(feature = "bot")]
+macro_rules! gateway_events {
+    ($callback:ident) => {
+        $callback!(
+            BotReadyMessage { data_about_bot: Ready },
+            CommandPermissionsUpdateMessage { permission: CommandPermissions },
+            AutoModerationRuleCreateMessage { rule: Rule },
+            AutoModerationRuleUpdateMessage { rule: Rule },
+            AutoModerationRuleDeleteMessage { rule: Rule },
+            AutoModerationActionExecutionMessage { execution: ActionExecution },
+            #[cfg(feature = "bot_cache")]
+            CacheReadMessage { guilds: Vec<GuildId> },
+            #[cfg(feature = "bot_cache")]
+            ShardsReadyMessage { total_shards: u32 },
+            ChannelCreateMessage { channel: GuildChannel },
+            CategoryCreateMessage { category: GuildChannel },
+            CategoryDeleteMessage { category: GuildChannel },
+            ChannelDeleteMessage { channel: GuildChannel, messages: Option<Vec<SMessage>> },
+            ChannelPinUpdateMessage { pin: ChannelPinsUpdateEvent },
+            ChannelUpdateMessage { old: Option<GuildChannel>, new: GuildChannel },
+            GuildAuditLogEntryCreateMessage { entry: AuditLogEntry, guild_id: GuildId },
+            GuildBanAdditionMessage { guild_id: GuildId, banned_user: User },
+            GuildBanRemovalMessage { guild_id: GuildId, unbanned_user: User },
+            GuildCreateMessage { guild: Guild, is_new: Option<bool> },
+            GuildDeleteMessage { incomplete: UnavailableGuild, full: Option<Guild> },
+            GuildEmojisUpdateMessage { guild_id: GuildId, current_state: HashMap<EmojiId, Emoji> },
+            GuildIntegrationsUpdateMessage { guild_id: GuildId },
+            GuildMemberAdditionMessage { new_member: Member },
+            GuildMemberRemovalMessage { guild_id: GuildId, user: User, member_data_if_available: Option<Member> },
+            GuildMemberUpdateMessage { old_if_available: Option<Member>, new: Option<Member>, event: GuildMemberUpdateEvent },
+            GuildMembersChunkMessage { chunk: GuildMembersChunkEvent },
+            GuildRoleCreateMessage { new: Role },
+            GuildRoleDeleteMessage { guild_id: GuildId, removed_role_id: RoleId, removed_role_data_if_available: Option<Role> },
+            GuildRoleUpdateMessage { old_data_if_available: Option<Role>, new: Role },
+            GuildStickersUpdateMessage { guild_id: GuildId, current_state: HashMap<StickerId, Sticker> },
+            GuildUpdateMessage { old_data_if_available: Option<Guild>, new_data: PartialGuild },
+            InviteCreateMessage { data: InviteCreateEvent },
+            InviteDeleteMessage { data: InviteDeleteEvent },
+            DiscordMessage { new_message: SMessage },
+            DiscordMessageDeleteMessage { channel_id: ChannelId, deleted_message_id: MessageId, guild_id: Option<GuildId> },
+            DiscordMessageDeleteBulkMessage { channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>, guild_id: Option<GuildId> },
+            DiscordMessageUpdateMessage { old_if_available: Option<SMessage>, new: Option<SMessage>, event: MessageUpdateEvent },
+            ReactionAddMessage { add_reaction: Reaction },
+            ReactionRemoveMessage { removed_reaction: Reaction },
+            ReactionRemoveAllMessage { channel_id: ChannelId, removed_from_message_id: MessageId },
+            ReactionRemoveEmojiMessage { removed_reactions: Reaction },
+            PresenceUpdateMessage { new_data: Presence },
+            ResumeMessage { event: ResumedEvent },
+            TypingStartMessage { event: TypingStartEvent },
+            UserUpdateMessage { old_data: Option<CurrentUser>, new: CurrentUser },
+            VoiceServerUpdateMessage { event: VoiceServerUpdateEvent },
+            VoiceStateUpdateMessage { old: Option<VoiceState>, new: VoiceState },
+            VoiceChannelStatusUpdateMessage { old: Option<String>, status: Option<String>, id: ChannelId, guild_id: GuildId },
+            WebhookUpdateMessage { guild_id: GuildId, belongs_to_channel_id: ChannelId },
+            InteractionCreateMessage { interaction: Interaction },
+            IntegrationCreateMessage { integration: Integration },
+            IntegrationUpdateMessage { integration: Integration },
+            StageInstanceCreateMessage { stage_instance: StageInstance },
+            StageInstanceUpdateMessage { stage_instance: StageInstance },
+            StageInstanceDeleteMessage { stage_instance: StageInstance },
+            ThreadCreateMessage { thread: GuildChannel },
+            ThreadUpdateMessage { old: Option<GuildChannel>, new: GuildChannel },
+            ThreadDeleteMessage { thread: PartialGuildChannel, full_thread_data: Option<GuildChannel> },
+            ThreadListSyncMessage { thread_list_sync: ThreadListSyncEvent },
+            ThreadMemberUpdateMessage { thread_member: ThreadMember },
+            ThreadMembersUpdateMessage { thread_members_update: ThreadMembersUpdateEvent },
+            GuildScheduledEventCreateMessage { event: ScheduledEvent },
+            GuildScheduledEventUpdateMessage { event: ScheduledEvent },
+            GuildScheduledEventDeleteMessage { event: ScheduledEvent },
+            GuildScheduledEventUserAddMessage { subscribed: GuildScheduledEventUserAddEvent },
+            GuildScheduledEventUserRemoveMessage { unsubscribed: GuildScheduledEventUserRemoveEvent },
+            EntitlementCreateMessage { entitlement: Entitlement },
+            EntitlementUpdateMessage { entitlement: Entitlement },
+            EntitlementDeleteMessage { entitlement: Entitlement },
+            PollVoteAddMessage { event: MessagePollVoteAddEvent },
+            PollVoteRemoveMessage { event: MessagePollVoteRemoveEvent },
+        );
+    };
+}
+
+/// Defines [`DiscordEventKind`](bot::DiscordEventKind) from the gateway events.
+#[cfg(feature = "bot")]
+macro_rules! discord_event_kinds {
+    ($($(#[$meta:meta])? $variant:ident { $($field:ident: $type:ty),* $(,)? }),* $(,)?) => {
+        /// Gateway event of a [`DiscordEvent`], named after the message it is written as and
+        /// with the same fields, without the [`Context`].
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(tag = "type", content = "data")]
+        #[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
+        pub enum DiscordEventKind {
+            $(
+                $(#[$meta])?
+                $variant { $($field: $type),* },
+            )*
+        }
+
+        impl DiscordEventKind {
+            /// Returns the event and its shard, or `None` if the message isn't a gateway event.
+            pub(crate) fn from_message(
+                message: &crate::messages::MessageCollectionBot,
+            ) -> Option<(ShardId, Self)> {
+                use crate::messages::MessageCollectionBot;
+
+                match message {
+                    $(
+                        $(#[$meta])?
+                        MessageCollectionBot::$variant(message) => Some((
+                            message.ctx.shard_id,
+                            Self::$variant { $($field: message.$field.clone()),* },
+                        )),
+                    )*
+                    _ => None,
+                }
+            }
+
+            /// Returns the message the event is written as, with the context.
+            #[cfg(feature = "recording")]
+            pub(crate) fn into_message(self, ctx: Context) -> crate::messages::MessageCollectionBot {
+                use crate::messages::MessageCollectionBot;
+
+                match self {
+                    $(
+                        $(#[$meta])?
+                        Self::$variant { $($field),* } => {
+                            MessageCollectionBot::$variant($variant { ctx, $($field),* })
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
 #[cfg(feature = "bot")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
 pub mod bot {
     //! This module contains all the bevy [Message] that are send by `bot` feature
 
     use bevy_ecs::prelude::Message;
+    use bevy_reflect::prelude::*;
+    use serde::{Deserialize, Serialize};
     use serenity::all::*;
     use serenity::model::channel::Message as SMessage;
     use std::collections::HashMap;
+    use std::time::SystemTime;
 
     /// Dispatched upon startup.
     ///
@@ -733,6 +875,272 @@ pub mod bot {
     pub struct RateLimitMessage {
         pub data: RatelimitInfo,
     }
+
+    /// Write this message to change the bot's presence while it is running.
+    ///
+    /// The presence is applied to every shard unless a shard is given, updates written before
+    /// the bot has connected are applied once it does.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use bevy::prelude::*;
+    /// use bevy_discord::messages::bot::SetBotPresenceMessage;
+    /// use bevy_discord::serenity::all::{ActivityData, OnlineStatus};
+    ///
+    /// fn show_player_count(mut presence: MessageWriter<SetBotPresenceMessage>) {
+    ///     presence.write(SetBotPresenceMessage::new(
+    ///         OnlineStatus::Online,
+    ///         Some(ActivityData::playing("42 players online")),
+    ///     ));
+    /// }
+    /// ```
+    #[derive(Message, Debug, Clone)]
+    pub struct SetBotPresenceMessage {
+        pub status: OnlineStatus,
+        pub activity: Option<ActivityData>,
+        /// Shard to update, `None` updates every shard.
+        pub shard: Option<ShardId>,
+    }
+
+    impl SetBotPresenceMessage {
+        /// Creates a presence update for every shard.
+        pub fn new(status: OnlineStatus, activity: Option<ActivityData>) -> Self {
+            Self {
+                status,
+                activity,
+                shard: None,
+            }
+        }
+
+        /// Only updates the presence of the given shard.
+        pub fn shard(mut self, shard: ShardId) -> Self {
+            self.shard = Some(shard);
+            self
+        }
+    }
+
+    /// Dispatched when the discord client fails to start or stops because of an error.
+    ///
+    /// The app keeps running. No more discord events are received unless the client is restarted
+    /// by [`DiscordBotConfig::restart_policy`](crate::config::DiscordBotConfig::restart_policy),
+    /// which is announced by a [`BotRestartMessage`]. Errors that aren't
+    /// [recoverable](crate::error::DiscordBotError::is_recoverable) are never restarted.
+    #[derive(Message, Debug, Clone)]
+    pub struct DiscordBotErrorMessage {
+        pub error: crate::error::DiscordBotError,
+    }
+
+    /// Dispatched when the discord client has stopped and is about to be restarted, see
+    /// [`DiscordBotConfig::restart_policy`](crate::config::DiscordBotConfig::restart_policy).
+    #[derive(Message, Debug, Clone)]
+    pub struct BotRestartMessage {
+        /// Number of the attempt, starting at 1 and reset once the client has connected again.
+        pub attempt: u32,
+        /// Time waited before the client is rebuilt.
+        pub delay: std::time::Duration,
+    }
+
+    /// Dispatched once the registered application commands of a guild, or the global ones, have
+    /// been synced with Discord, see [`slash_commands`](crate::slash_commands).
+    #[derive(Message, Debug, Clone)]
+    pub struct CommandSyncMessage {
+        /// Guild whose commands were synced, `None` for the global commands.
+        pub guild_id: Option<GuildId>,
+        pub result:
+            Result<crate::slash_commands::CommandSyncReport, crate::error::DiscordHttpError>,
+    }
+
+    /// Dispatched when an interaction was never answered, see
+    /// [`DiscordBotConfig::interaction_watchdog`](crate::config::DiscordBotConfig::interaction_watchdog).
+    #[derive(Message, Debug, Clone)]
+    pub struct UnansweredInteractionMessage {
+        pub interaction_id: InteractionId,
+        pub kind: InteractionType,
+        /// Whether the interaction was deferred by the watchdog.
+        pub deferred: bool,
+    }
+
+    /// Dispatched when a command without a handler was used, see
+    /// [`slash_commands`](crate::slash_commands).
+    ///
+    /// Only dispatched once a handler has been registered for any command.
+    #[derive(Message, Debug, Clone)]
+    pub struct UnhandledCommandMessage {
+        /// Name of the command followed by its subcommand group and subcommand.
+        pub path: String,
+        pub interaction: CommandInteraction,
+    }
+
+    /// Dispatched whenever a button or select menu is used and no
+    /// [`ComponentHandler`](crate::message_components::ComponentHandler) matches its `custom_id`.
+    #[derive(Message, Debug, Clone)]
+    pub struct UnhandledComponentMessage {
+        pub custom_id: String,
+        pub interaction: ComponentInteraction,
+    }
+
+    /// Dispatched once the discord client has stopped without an error, e.g. after its shards
+    /// were closed through the shard manager, or once it has been shut down because the app is
+    /// exiting.
+    ///
+    /// When the app is exiting, it is written in the [`PostUpdate`](bevy_app::PostUpdate) of
+    /// the last frame, read it from a system that runs after
+    /// [`DiscordSystems`](crate::DiscordSystems).
+    #[derive(Message, Debug, Clone)]
+    pub struct BotDisconnectedMessage {
+        /// `false` if the shards or the pending outbound work didn't finish within
+        /// [`DiscordBotConfig::shutdown_timeout`](crate::config::DiscordBotConfig::shutdown_timeout)
+        /// when the app exited, or if the recording replayed by
+        /// [`DiscordReplayPlugin`](crate::DiscordReplayPlugin) couldn't be read to the end.
+        pub graceful: bool,
+    }
+
+    /// Dispatched for every gateway event, alongside its own message, when
+    /// [`DiscordBotConfig::event_stream`](crate::config::DiscordBotConfig::event_stream) is
+    /// enabled.
+    ///
+    /// Unlike the messages of each event, which are read by different systems, they are all
+    /// read in the order they were queued, which is the order of their `sequence`. Useful for
+    /// audit logging or replication.
+    ///
+    /// Serenity handles the gateway events concurrently, so events received at nearly the same
+    /// time, e.g. a [`DiscordMessageUpdateMessage`] and the [`DiscordMessageDeleteMessage`] that
+    /// followed it, may be queued in either order.
+    #[derive(Message, Debug, Clone, Serialize, Deserialize, Reflect)]
+    #[reflect(opaque, Debug, Clone, Serialize, Deserialize)]
+    pub struct DiscordEvent {
+        /// Position of the event among all the events queued by the bot, starting at `1`.
+        pub sequence: u64,
+        /// Shard that received the event.
+        pub shard_id: u32,
+        pub received_at: SystemTime,
+        pub kind: DiscordEventKind,
+    }
+
+    gateway_events!(discord_event_kinds);
+}
+
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub mod http {
+    //! This module contains all the bevy [Message] that are used by the `http` feature
+
+    use bevy_ecs::prelude::Message;
+    use serenity::all::*;
+    use serenity::model::channel::Message as SMessage;
+
+    /// Write this message to send a request to Discord without writing any async code.
+    ///
+    /// The request is executed on the [tokio runtime](crate::runtime::tokio_runtime) with
+    /// [DiscordHttpResource](crate::res::DiscordHttpResource). Requests written before the
+    /// resource is available, e.g. before the bot is ready, are executed once it is.
+    ///
+    /// Use [`DiscordHttpResource::request`](crate::res::DiscordHttpResource::request) instead
+    /// if you need to know the outcome of the request.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use bevy::prelude::*;
+    /// use bevy_discord::messages::bot::DiscordMessage;
+    /// use bevy_discord::messages::http::DiscordRequest;
+    /// use bevy_discord::serenity::all::{CreateMessage, ReactionType};
+    ///
+    /// fn pong(
+    ///     mut messages: MessageReader<DiscordMessage>,
+    ///     mut requests: MessageWriter<DiscordRequest>,
+    /// ) {
+    ///     for message in messages.read() {
+    ///         if message.new_message.content == "!ping" {
+    ///             requests.write(DiscordRequest::AddReaction {
+    ///                 channel_id: message.new_message.channel_id,
+    ///                 message_id: message.new_message.id,
+    ///                 reaction: ReactionType::Unicode("🏓".to_string()),
+    ///             });
+    ///             requests.write(DiscordRequest::SendMessage {
+    ///                 channel_id: message.new_message.channel_id,
+    ///                 message: CreateMessage::new().content("Pong!"),
+    ///             });
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[derive(Message, Debug, Clone)]
+    pub enum DiscordRequest {
+        /// Sends a message to the channel.
+        SendMessage {
+            channel_id: ChannelId,
+            message: CreateMessage,
+        },
+        /// Edits a message in the channel.
+        EditMessage {
+            channel_id: ChannelId,
+            message_id: MessageId,
+            message: EditMessage,
+        },
+        /// Reacts to a message in the channel.
+        AddReaction {
+            channel_id: ChannelId,
+            message_id: MessageId,
+            reaction: ReactionType,
+        },
+        /// Creates a thread in the channel, attached to `message_id` if one is given.
+        CreateThread {
+            channel_id: ChannelId,
+            message_id: Option<MessageId>,
+            thread: CreateThread<'static>,
+        },
+        /// Responds to an interaction, e.g. a slash command.
+        RespondInteraction {
+            interaction_id: InteractionId,
+            token: String,
+            response: CreateInteractionResponse,
+        },
+    }
+
+    /// Identifies a request sent with
+    /// [`DiscordHttpResource::request`](crate::res::DiscordHttpResource::request), so that its
+    /// [`DiscordRequestCompleted`] can be told apart from the others.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct DiscordRequestId(pub(crate) u64);
+
+    /// What Discord returned for a successful [`DiscordRequest`].
+    #[derive(Debug, Clone)]
+    pub enum DiscordResponse {
+        /// The message that was sent or edited.
+        Message(Box<SMessage>),
+        /// The thread that was created.
+        Thread(Box<GuildChannel>),
+        /// The request doesn't return anything, e.g. a reaction was added.
+        Empty,
+    }
+
+    /// Dispatched once a request sent with
+    /// [`DiscordHttpResource::request`](crate::res::DiscordHttpResource::request) has finished.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use bevy::prelude::*;
+    /// use bevy_discord::messages::http::{DiscordRequestCompleted, DiscordResponse};
+    ///
+    /// fn log_sent_messages(mut completed: MessageReader<DiscordRequestCompleted>) {
+    ///     for completed in completed.read() {
+    ///         match &completed.result {
+    ///             Ok(DiscordResponse::Message(message)) => info!("Sent message {}", message.id),
+    ///             Ok(_) => {}
+    ///             Err(err) => warn!("Request {:?} failed: {}", completed.id, err),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[derive(Message, Debug, Clone)]
+    pub struct DiscordRequestCompleted {
+        /// Id returned when the request was sent.
+        pub id: DiscordRequestId,
+        pub result: Result<DiscordResponse, crate::error::DiscordHttpError>,
+    }
 }
 
 #[cfg(feature = "rich_presence")]
@@ -754,6 +1162,16 @@ pub mod rich_presence {
     #[derive(Message, Debug)]
     pub struct ErrorMessage(pub Event);
 
+    /// Fires when the Rich Presence client couldn't be created, e.g. because Discord isn't
+    /// running, or when the connection to Discord reports an error.
+    ///
+    /// When the client couldn't be created, [DiscordRichPresenceRes](crate::res::DiscordRichPresenceRes)
+    /// isn't inserted.
+    #[derive(Message, Debug, Clone)]
+    pub struct RichPresenceErrorMessage {
+        pub error: crate::error::RichPresenceError,
+    }
+
     /// Sent by Discord upon receipt of our Handshake message, the user is the current user logged
     /// in to the Discord we connected to.
     #[derive(Message, Debug, Clone)]
@@ -820,8 +1238,130 @@ pub mod rich_presence {
     pub struct RelationshipUpdateMessage(pub Arc<Relationship>);
 }
 
+/// Defines the payload messages of the gateway events, and their conversion from the messages
+/// of [`bot`].
+#[cfg(feature = "bot")]
+macro_rules! payload_messages {
+    ($($(#[$meta:meta])? $variant:ident { $($field:ident: $type:ty),* $(,)? }),* $(,)?) => {
+        pastey::paste! {
+            $(
+                #[doc = concat!(
+                    "Payload of [`", stringify!($variant), "`](super::bot::", stringify!($variant),
+                    "), without its context."
+                )]
+                $(#[$meta])?
+                #[derive(Message, Debug, Clone, Serialize, Deserialize, Reflect)]
+                #[reflect(opaque, Debug, Clone, Serialize, Deserialize)]
+                pub struct [<$variant Payload>] {
+                    /// Shard that received the event.
+                    pub shard_id: u32,
+                    $(pub $field: $type,)*
+                }
+            )*
+
+            /// Adds the payload messages to the app.
+            pub(crate) fn add_payload_messages(app: &mut bevy_app::App) {
+                $(
+                    $(#[$meta])?
+                    app.add_message::<[<$variant Payload>]>();
+                )*
+            }
+
+            impl MessageCollectionBot {
+                /// Returns the payload message of a gateway event, or `None` for the other
+                /// messages.
+                pub(crate) fn to_payload(&self) -> Option<Self> {
+                    match self {
+                        $(
+                            $(#[$meta])?
+                            Self::$variant(message) => Some(Self::[<$variant Payload>](
+                                [<$variant Payload>] {
+                                    shard_id: message.ctx.shard_id.0,
+                                    $($field: message.$field.clone(),)*
+                                },
+                            )),
+                        )*
+                        _ => None,
+                    }
+                }
+
+                /// Like [`to_payload`](Self::to_payload) without cloning the payload, the other
+                /// messages are returned as they are.
+                #[allow(clippy::result_large_err)]
+                pub(crate) fn into_payload(self) -> Result<Self, Self> {
+                    match self {
+                        $(
+                            $(#[$meta])?
+                            Self::$variant($variant { ctx, $($field),* }) => Ok(
+                                Self::[<$variant Payload>]([<$variant Payload>] {
+                                    shard_id: ctx.shard_id.0,
+                                    $($field,)*
+                                }),
+                            ),
+                        )*
+                        message => Err(message),
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+pub mod payload {
+    //! Context-free versions of the gateway messages of [`bot`](super::bot), written instead of
+    //! them or alongside them depending on
+    //! [`DiscordBotConfig::message_mode`](crate::config::DiscordBotConfig::message_mode).
+    //!
+    //! Each payload message is named after its message with a `Payload` suffix, e.g.
+    //! [`DiscordMessagePayload`] for [`DiscordMessage`](super::bot::DiscordMessage), and has the
+    //! same fields with the id of the shard instead of the [`Context`](serenity::all::Context).
+    //! Without the context they can be built by hand, and they implement `Serialize`,
+    //! `Deserialize` and `Reflect`, as opaque values. The HTTP client is available through
+    //! [`DiscordHttpResource`](crate::res::DiscordHttpResource).
+    //!
+    //! # Example
+    //!
+    //! ```rust,no_run
+    //! use bevy::prelude::*;
+    //! use bevy_discord::DiscordBotPlugin;
+    //! use bevy_discord::config::{BotMessageMode, DiscordBotConfig};
+    //! use bevy_discord::messages::payload::DiscordMessagePayload;
+    //!
+    //! fn log_messages(mut messages: MessageReader<DiscordMessagePayload>) {
+    //!     for message in messages.read() {
+    //!         info!("shard {}: {}", message.shard_id, message.new_message.content);
+    //!     }
+    //! }
+    //!
+    //! let config = DiscordBotConfig::default()
+    //!     .token("your-bot-token".to_string())
+    //!     .message_mode(BotMessageMode::Payload);
+    //!
+    //! App::new()
+    //!     .add_plugins(DiscordBotPlugin::new(config))
+    //!     .add_systems(Update, log_messages)
+    //!     .run();
+    //! ```
+
+    use bevy_ecs::prelude::Message;
+    use bevy_reflect::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use serenity::all::*;
+    use serenity::model::channel::Message as SMessage;
+    use std::collections::HashMap;
+
+    use super::MessageCollectionBot;
+    use super::bot::*;
+
+    gateway_events!(payload_messages);
+}
+
 #[cfg(feature = "bot")]
 use bot::*;
+#[cfg(feature = "bot")]
+use payload::*;
 #[cfg(feature = "rich_presence")]
 use rich_presence::*;
 
@@ -829,6 +1369,7 @@ use rich_presence::*;
 create_message_collection_and_handler!(
     MessageCollectionBot,
     bot,
+    crate::DiscordBotPlugin,
     CommandPermissionsUpdateMessage,
     AutoModerationRuleCreateMessage,
     AutoModerationRuleUpdateMessage,
@@ -903,12 +1444,152 @@ create_message_collection_and_handler!(
     PollVoteAddMessage,
     PollVoteRemoveMessage,
     RateLimitMessage,
+    DiscordBotErrorMessage,
+    BotDisconnectedMessage,
+    BotRestartMessage,
+    CommandSyncMessage,
+    UnansweredInteractionMessage,
+    DiscordEvent,
+    BotReadyMessagePayload,
+    CommandPermissionsUpdateMessagePayload,
+    AutoModerationRuleCreateMessagePayload,
+    AutoModerationRuleUpdateMessagePayload,
+    AutoModerationRuleDeleteMessagePayload,
+    AutoModerationActionExecutionMessagePayload,
+    #[cfg(feature = "bot_cache")]
+    CacheReadMessagePayload,
+    #[cfg(feature = "bot_cache")]
+    ShardsReadyMessagePayload,
+    ChannelCreateMessagePayload,
+    CategoryCreateMessagePayload,
+    CategoryDeleteMessagePayload,
+    ChannelDeleteMessagePayload,
+    ChannelPinUpdateMessagePayload,
+    ChannelUpdateMessagePayload,
+    GuildAuditLogEntryCreateMessagePayload,
+    GuildBanAdditionMessagePayload,
+    GuildBanRemovalMessagePayload,
+    GuildCreateMessagePayload,
+    GuildDeleteMessagePayload,
+    GuildEmojisUpdateMessagePayload,
+    GuildIntegrationsUpdateMessagePayload,
+    GuildMemberAdditionMessagePayload,
+    GuildMemberRemovalMessagePayload,
+    GuildMemberUpdateMessagePayload,
+    GuildMembersChunkMessagePayload,
+    GuildRoleCreateMessagePayload,
+    GuildRoleDeleteMessagePayload,
+    GuildRoleUpdateMessagePayload,
+    GuildStickersUpdateMessagePayload,
+    GuildUpdateMessagePayload,
+    InviteCreateMessagePayload,
+    InviteDeleteMessagePayload,
+    DiscordMessagePayload,
+    DiscordMessageDeleteMessagePayload,
+    DiscordMessageDeleteBulkMessagePayload,
+    DiscordMessageUpdateMessagePayload,
+    ReactionAddMessagePayload,
+    ReactionRemoveMessagePayload,
+    ReactionRemoveAllMessagePayload,
+    ReactionRemoveEmojiMessagePayload,
+    PresenceUpdateMessagePayload,
+    ResumeMessagePayload,
+    TypingStartMessagePayload,
+    UserUpdateMessagePayload,
+    VoiceServerUpdateMessagePayload,
+    VoiceStateUpdateMessagePayload,
+    VoiceChannelStatusUpdateMessagePayload,
+    WebhookUpdateMessagePayload,
+    InteractionCreateMessagePayload,
+    IntegrationCreateMessagePayload,
+    IntegrationUpdateMessagePayload,
+    StageInstanceCreateMessagePayload,
+    StageInstanceUpdateMessagePayload,
+    StageInstanceDeleteMessagePayload,
+    ThreadCreateMessagePayload,
+    ThreadUpdateMessagePayload,
+    ThreadDeleteMessagePayload,
+    ThreadListSyncMessagePayload,
+    ThreadMemberUpdateMessagePayload,
+    ThreadMembersUpdateMessagePayload,
+    GuildScheduledEventCreateMessagePayload,
+    GuildScheduledEventUpdateMessagePayload,
+    GuildScheduledEventDeleteMessagePayload,
+    GuildScheduledEventUserAddMessagePayload,
+    GuildScheduledEventUserRemoveMessagePayload,
+    EntitlementCreateMessagePayload,
+    EntitlementUpdateMessagePayload,
+    EntitlementDeleteMessagePayload,
+    PollVoteAddMessagePayload,
+    PollVoteRemoveMessagePayload,
+);
+
+/// Gateway messages that the plugins of this crate read with their context, which are still
+/// written in [`BotMessageMode::Payload`](crate::config::BotMessageMode::Payload).
+#[cfg(feature = "bot")]
+#[derive(bevy_ecs::resource::Resource, Debug, Clone, Default)]
+pub(crate) struct ContextReaders(
+    std::sync::Arc<std::sync::RwLock<std::collections::HashSet<std::any::TypeId>>>,
 );
 
+#[cfg(feature = "bot")]
+impl ContextReaders {
+    fn contains(&self, type_id: std::any::TypeId) -> bool {
+        self.0
+            .read()
+            .expect("context readers lock shouldn't be poisoned")
+            .contains(&type_id)
+    }
+}
+
+/// Keeps writing `M` with its context in
+/// [`BotMessageMode::Payload`](crate::config::BotMessageMode::Payload), called by the plugins
+/// that read it.
+#[cfg(feature = "bot")]
+pub(crate) fn read_with_context<M: bevy_ecs::message::Message>(app: &mut bevy_app::App) {
+    app.world_mut()
+        .get_resource_or_init::<ContextReaders>()
+        .0
+        .write()
+        .expect("context readers lock shouldn't be poisoned")
+        .insert(std::any::TypeId::of::<M>());
+}
+
+#[cfg(feature = "bot")]
+impl MessageCollectionBot {
+    /// Returns the message to write for this one with the mode, followed by its payload when
+    /// both are written.
+    pub(crate) fn with_mode(
+        self,
+        mode: crate::config::BotMessageMode,
+        readers: &ContextReaders,
+    ) -> (Self, Option<Self>) {
+        use crate::channel::QueuedMessage;
+        use crate::config::BotMessageMode;
+
+        match mode {
+            BotMessageMode::Context => (self, None),
+            BotMessageMode::Both => {
+                let payload = self.to_payload();
+                (self, payload)
+            }
+            BotMessageMode::Payload if readers.contains(self.message_type_id()) => {
+                let payload = self.to_payload();
+                (self, payload)
+            }
+            BotMessageMode::Payload => match self.into_payload() {
+                Ok(payload) => (payload, None),
+                Err(message) => (message, None),
+            },
+        }
+    }
+}
+
 #[cfg(feature = "rich_presence")]
 create_message_collection_and_handler!(
     MessageCollectionRichPresence,
     rich_presence,
+    crate::DiscordRichPresencePlugin,
     ErrorMessage,
     RpReadyMessage,
     DisconnectedMessage,
@@ -918,5 +1599,6 @@ create_message_collection_and_handler!(
     ActivityJoinRequestMessage,
     ActivityInviteMessage,
     OverlayUpdateMessage,
-    RelationshipUpdateMessage
+    RelationshipUpdateMessage,
+    RichPresenceErrorMessage,
 );
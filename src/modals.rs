@@ -0,0 +1,204 @@
+//! Modal dialogs with typed submissions.
+//!
+//! Describe the text inputs of a modal with a struct deriving [`DiscordModal`], and register it
+//! with [`DiscordModalAppExt::add_discord_modal`]. Open it in response to a command or a button
+//! with [`CommandInvoked::open_modal`](crate::slash_commands::CommandInvoked::open_modal) or
+//! [`ComponentInteracted::open_modal`](crate::message_components::ComponentInteracted::open_modal),
+//! and every submission is dispatched as a [`ModalSubmitted`] with the values of the inputs.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::messages::http::DiscordRequest;
+//! use bevy_discord::modals::{DiscordModal, DiscordModalAppExt, ModalSubmitted};
+//! use bevy_discord::serenity::all::{CreateCommand, CreateInteractionResponse};
+//! use bevy_discord::slash_commands::{CommandInvoked, DiscordCommandAppExt};
+//!
+//! #[derive(DiscordModal)]
+//! #[modal(title = "Report a bug")]
+//! struct BugReport {
+//!     #[input(label = "Summary", max_length = 100)]
+//!     summary: String,
+//!     #[input(label = "Steps to reproduce", paragraph)]
+//!     steps: Option<String>,
+//! }
+//!
+//! fn open_report(In(invoked): In<CommandInvoked>, mut requests: MessageWriter<DiscordRequest>) {
+//!     requests.write(invoked.open_modal::<BugReport>());
+//! }
+//!
+//! fn collect_reports(
+//!     mut reports: MessageReader<ModalSubmitted<BugReport>>,
+//!     mut requests: MessageWriter<DiscordRequest>,
+//! ) {
+//!     for report in reports.read() {
+//!         info!("{} reported: {}", report.user.name, report.modal.summary);
+//!         requests.write(report.respond(CreateInteractionResponse::Acknowledge));
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_discord_command(CreateCommand::new("report").description("Report a bug"))
+//!     .add_discord_command_system("report", open_report)
+//!     .add_discord_modal::<BugReport>()
+//!     .add_systems(Update, collect_reports)
+//!     .run();
+//! ```
+
+use bevy_app::{App, Update};
+use bevy_ecs::prelude::*;
+use serenity::all::{
+    ActionRow, CreateInteractionResponse, CreateModal, Interaction, InteractionId,
+    ModalInteraction, User,
+};
+use std::fmt;
+use tracing::warn;
+
+use crate::DiscordSystems;
+use crate::messages::bot::InteractionCreateMessage;
+use crate::messages::http::DiscordRequest;
+use crate::messages::{read_with_context, send_events_bot};
+
+pub use bevy_discord_macros::DiscordModal;
+
+/// A modal whose text inputs are described by a struct, derive it with
+/// [`DiscordModal`](derive@DiscordModal).
+///
+/// Fields are `String`s, or `Option<String>`s for inputs that aren't required. Discord limits
+/// modals to 5 inputs, so the derive rejects structs with more fields.
+pub trait DiscordModal: Sized + Send + Sync + 'static {
+    /// `custom_id` of the modal, used to tell its submissions apart.
+    const CUSTOM_ID: &'static str;
+
+    /// Returns the modal that is shown to the user.
+    fn create_modal() -> CreateModal;
+
+    /// Builds the modal from the submitted text inputs.
+    fn from_components(components: &[ActionRow]) -> Result<Self, ModalParseError>;
+}
+
+/// Errors returned by [`DiscordModal::from_components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModalParseError {
+    /// A required text input wasn't submitted.
+    MissingInput(String),
+}
+
+impl fmt::Display for ModalParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInput(custom_id) => write!(f, "the input `{custom_id}` is missing"),
+        }
+    }
+}
+
+impl std::error::Error for ModalParseError {}
+
+/// Dispatched whenever a modal registered with [`DiscordModalAppExt::add_discord_modal`] is
+/// submitted.
+#[derive(Message, Debug, Clone)]
+pub struct ModalSubmitted<T: DiscordModal> {
+    pub modal: T,
+    pub interaction: ModalInteraction,
+    /// User that submitted the modal.
+    pub user: User,
+}
+
+impl<T: DiscordModal> ModalSubmitted<T> {
+    /// Returns the request responding to the submission, write it as a message.
+    pub fn respond(&self, response: CreateInteractionResponse) -> DiscordRequest {
+        DiscordRequest::RespondInteraction {
+            interaction_id: self.interaction.id,
+            token: self.interaction.token.clone(),
+            response,
+        }
+    }
+}
+
+/// Returns the request responding to the interaction with the modal.
+pub(crate) fn open_modal<T: DiscordModal>(
+    interaction_id: InteractionId,
+    token: &str,
+) -> DiscordRequest {
+    DiscordRequest::RespondInteraction {
+        interaction_id,
+        token: token.to_string(),
+        response: CreateInteractionResponse::Modal(T::create_modal()),
+    }
+}
+
+/// Extension trait for [`App`] to receive the submissions of modals, see the [module](self) for
+/// more information.
+pub trait DiscordModalAppExt {
+    /// Dispatches a [`ModalSubmitted`] whenever the modal is submitted.
+    fn add_discord_modal<T: DiscordModal>(&mut self) -> &mut Self;
+}
+
+impl DiscordModalAppExt for App {
+    fn add_discord_modal<T: DiscordModal>(&mut self) -> &mut Self {
+        read_with_context::<InteractionCreateMessage>(self);
+
+        self.add_message::<ModalSubmitted<T>>().add_systems(
+            Update,
+            read_modal_submissions::<T>
+                .after(send_events_bot)
+                .in_set(DiscordSystems),
+        )
+    }
+}
+
+fn read_modal_submissions<T: DiscordModal>(
+    mut interactions: MessageReader<InteractionCreateMessage>,
+    mut submissions: MessageWriter<ModalSubmitted<T>>,
+) {
+    for event in interactions.read() {
+        let Interaction::Modal(interaction) = &event.interaction else {
+            continue;
+        };
+
+        if interaction.data.custom_id != T::CUSTOM_ID {
+            continue;
+        }
+
+        match T::from_components(&interaction.data.components) {
+            Ok(modal) => {
+                submissions.write(ModalSubmitted {
+                    modal,
+                    interaction: interaction.clone(),
+                    user: interaction.user.clone(),
+                });
+            }
+            Err(err) => warn!("Unable to parse the modal `{}`: {}", T::CUSTOM_ID, err),
+        }
+    }
+}
+
+/// Used by the code generated by [`DiscordModal`](derive@DiscordModal).
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+    use serenity::all::ActionRowComponent;
+
+    pub fn required(components: &[ActionRow], custom_id: &str) -> Result<String, ModalParseError> {
+        optional(components, custom_id)
+            .ok_or_else(|| ModalParseError::MissingInput(custom_id.to_string()))
+    }
+
+    /// Returns `None` for inputs that were left empty.
+    pub fn optional(components: &[ActionRow], custom_id: &str) -> Option<String> {
+        components
+            .iter()
+            .flat_map(|row| &row.components)
+            .find_map(|component| match component {
+                ActionRowComponent::InputText(input) if input.custom_id == custom_id => {
+                    input.value.clone()
+                }
+                _ => None,
+            })
+            .filter(|value| !value.is_empty())
+    }
+}
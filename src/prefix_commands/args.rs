@@ -0,0 +1,214 @@
+use serenity::all::{ChannelId, RoleId, UserId};
+use std::fmt;
+use std::time::Duration;
+
+/// Splits the arguments on whitespace, keeping the text between double quotes together, and
+/// returns them along with the byte offset they start at in `input`.
+///
+/// A `\` escapes the next character, e.g. `\"` is a literal quote.
+pub(crate) fn tokenize(input: &str) -> Vec<(usize, String)> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+    let mut in_quotes = false;
+    let mut chars = input.char_indices();
+
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    current.push(escaped);
+                    start.get_or_insert(index);
+                }
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                start.get_or_insert(index);
+            }
+            char if char.is_whitespace() && !in_quotes => {
+                if let Some(start) = start.take() {
+                    args.push((start, std::mem::take(&mut current)));
+                }
+            }
+            char => {
+                current.push(char);
+                start.get_or_insert(index);
+            }
+        }
+    }
+
+    if let Some(start) = start {
+        args.push((start, current));
+    }
+
+    args
+}
+
+/// Types that prefix command arguments can be converted to, see
+/// [`PrefixInvoked::arg`](super::PrefixInvoked::arg).
+pub trait PrefixArgument: Sized {
+    /// Returns `None` if the argument isn't valid.
+    fn from_arg(arg: &str) -> Option<Self>;
+}
+
+impl PrefixArgument for String {
+    fn from_arg(arg: &str) -> Option<Self> {
+        Some(arg.to_string())
+    }
+}
+
+macro_rules! impl_prefix_argument_from_str {
+    ($($type:ty),*) => {
+        $(
+            impl PrefixArgument for $type {
+                fn from_arg(arg: &str) -> Option<Self> {
+                    arg.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_prefix_argument_from_str!(i64, u64, i32, u32, f64, f32);
+
+/// Accepts `true`, `yes` and `on`, or `false`, `no` and `off`.
+impl PrefixArgument for bool {
+    fn from_arg(arg: &str) -> Option<Self> {
+        match arg.to_lowercase().as_str() {
+            "true" | "yes" | "on" => Some(true),
+            "false" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the id of a mention such as `<@123>`, or of a raw id.
+fn parse_mention(arg: &str, prefixes: &[&str]) -> Option<u64> {
+    let id = match arg.strip_prefix('<').and_then(|arg| arg.strip_suffix('>')) {
+        Some(mention) => prefixes
+            .iter()
+            .find_map(|prefix| mention.strip_prefix(prefix))?,
+        None => arg,
+    };
+
+    id.parse().ok().filter(|id| *id != 0)
+}
+
+/// Accepts a mention such as `<@123>` or an id.
+impl PrefixArgument for UserId {
+    fn from_arg(arg: &str) -> Option<Self> {
+        parse_mention(arg, &["@!", "@"]).map(UserId::new)
+    }
+}
+
+/// Accepts a mention such as `<#123>` or an id.
+impl PrefixArgument for ChannelId {
+    fn from_arg(arg: &str) -> Option<Self> {
+        parse_mention(arg, &["#"]).map(ChannelId::new)
+    }
+}
+
+/// Accepts a mention such as `<@&123>` or an id.
+impl PrefixArgument for RoleId {
+    fn from_arg(arg: &str) -> Option<Self> {
+        parse_mention(arg, &["@&"]).map(RoleId::new)
+    }
+}
+
+/// Accepts amounts of days, hours, minutes and seconds such as `1h30m`, `2d` or `45s`.
+impl PrefixArgument for Duration {
+    fn from_arg(arg: &str) -> Option<Self> {
+        let mut total = 0;
+        let mut amount = String::new();
+
+        for char in arg.to_lowercase().chars() {
+            if char.is_ascii_digit() {
+                amount.push(char);
+                continue;
+            }
+
+            let unit = match char {
+                'd' => 24 * 60 * 60,
+                'h' => 60 * 60,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            let amount: u64 = std::mem::take(&mut amount).parse().ok()?;
+            total = amount.checked_mul(unit)?.checked_add(total)?;
+        }
+
+        if !amount.is_empty() || arg.is_empty() {
+            return None;
+        }
+
+        Some(Duration::from_secs(total))
+    }
+}
+
+/// Errors returned by [`PrefixInvoked::arg`](super::PrefixInvoked::arg).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentError {
+    /// The command has no argument with that name.
+    UnknownArgument(String),
+    /// The argument wasn't given.
+    MissingArgument(String),
+    /// The argument couldn't be converted.
+    InvalidArgument { name: String, value: String },
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownArgument(name) => write!(f, "the command has no argument `{name}`"),
+            Self::MissingArgument(name) => write!(f, "the argument `{name}` is missing"),
+            Self::InvalidArgument { name, value } => {
+                write!(f, "`{value}` isn't a valid value for `{name}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgumentError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        tokenize(input).into_iter().map(|(_, arg)| arg).collect()
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_text_together() {
+        assert_eq!(args(r#"ban  "Some Player" 7"#), ["ban", "Some Player", "7"]);
+        assert_eq!(args(r#"say \"hi\" """#), ["say", "\"hi\"", ""]);
+        assert!(args("   ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_returns_where_the_arguments_start() {
+        assert_eq!(
+            tokenize(r#"mute  "a b" c"#),
+            [
+                (0, "mute".to_string()),
+                (6, "a b".to_string()),
+                (12, "c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn durations_are_parsed() {
+        assert_eq!(Duration::from_arg("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(
+            Duration::from_arg("2D"),
+            Some(Duration::from_secs(2 * 24 * 60 * 60))
+        );
+        assert_eq!(Duration::from_arg("45s"), Some(Duration::from_secs(45)));
+
+        for invalid in ["", "10", "h", "1w", "-1s", "99999999999999999999d"] {
+            assert_eq!(Duration::from_arg(invalid), None, "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Text commands starting with a prefix, such as `!ban @player 7`.
+//!
+//! Describe a command with [`PrefixCommand`] and handle it with
+//! [`DiscordPrefixCommandAppExt::add_prefix_command`]. Every [`DiscordMessage`] starting with the
+//! prefix of its guild, see [`CommandPrefixes`], is split into arguments and dispatched to the
+//! systems of the command as a [`PrefixInvoked`]. Arguments are separated by whitespace, unless
+//! they are between double quotes, and converted with [`PrefixInvoked::arg`].
+//!
+//! A help command listing the registered commands is answered, see
+//! [`PrefixCommands::help_command`]. Commands used with the wrong number of arguments are
+//! answered with their usage.
+//!
+//! Reading the content of messages requires the `MESSAGE_CONTENT` intent.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::prefix_commands::{DiscordPrefixCommandAppExt, PrefixCommand, PrefixInvoked};
+//! use bevy_discord::serenity::all::UserId;
+//! use std::time::Duration;
+//!
+//! fn mute(In(invoked): In<PrefixInvoked>) {
+//!     let (Ok(user), Ok(duration)) = (
+//!         invoked.arg::<UserId>("user"),
+//!         invoked.arg::<Duration>("duration"),
+//!     ) else {
+//!         return;
+//!     };
+//!
+//!     info!("Muting {} for {:?}", user, duration);
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_prefix_command(
+//!         PrefixCommand::new("mute")
+//!             .description("Mutes a player")
+//!             .arg("user", "Player to mute")
+//!             .arg("duration", "How long, e.g. 1h30m")
+//!             .rest_arg("reason", "Why the player is muted"),
+//!         mute,
+//!     )
+//!     .run();
+//! ```
+//!
+//! [`DiscordMessage`]: crate::messages::bot::DiscordMessage
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+
+use crate::checks::CommandChecks;
+
+mod args;
+mod routing;
+
+pub use args::{ArgumentError, PrefixArgument};
+pub use routing::{CommandPrefixes, PrefixCommands, PrefixInvoked};
+
+/// Definition of a prefix command, see the [module](self) for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixCommand {
+    pub(crate) name: String,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) description: String,
+    pub(crate) args: Vec<PrefixArgumentDefinition>,
+    pub(crate) checks: Option<CommandChecks>,
+}
+
+/// Argument of a [`PrefixCommand`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixArgumentDefinition {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+    /// Whether the argument takes the rest of the message.
+    pub rest: bool,
+}
+
+impl PrefixCommand {
+    /// Creates a command, its name is matched without case.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into().to_lowercase(),
+            aliases: Vec::new(),
+            description: String::new(),
+            args: Vec::new(),
+            checks: None,
+        }
+    }
+
+    /// Sets the description shown in the help.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Adds another name the command can be used with.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into().to_lowercase());
+        self
+    }
+
+    /// Adds a required argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it comes after an optional or rest argument.
+    pub fn arg(self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.add_arg(name, description, true, false)
+    }
+
+    /// Adds an argument that can be left out, it must come after the required ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it comes after the rest argument.
+    pub fn optional_arg(self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.add_arg(name, description, false, false)
+    }
+
+    /// Adds an argument taking the rest of the message, it must be the last one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the command already has a rest argument.
+    pub fn rest_arg(self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.add_arg(name, description, false, true)
+    }
+
+    /// Evaluates the checks before the command is dispatched, see [`checks`](crate::checks).
+    pub fn checks(mut self, checks: CommandChecks) -> Self {
+        self.checks = Some(checks);
+        self
+    }
+
+    fn add_arg(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        required: bool,
+        rest: bool,
+    ) -> Self {
+        let name = name.into();
+
+        assert!(
+            !self.args.last().is_some_and(|arg| arg.rest),
+            "the argument `{name}` of `{}` comes after its rest argument",
+            self.name
+        );
+        assert!(
+            !required || self.args.iter().all(|arg| arg.required),
+            "the required argument `{name}` of `{}` comes after an optional one",
+            self.name
+        );
+
+        self.args.push(PrefixArgumentDefinition {
+            name,
+            description: description.into(),
+            required,
+            rest,
+        });
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[PrefixArgumentDefinition] {
+        &self.args
+    }
+
+    /// Returns how the command is used, e.g. `!mute <user> <duration> [reason...]`.
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut usage = format!("{prefix}{}", self.name);
+
+        for arg in &self.args {
+            let name = if arg.rest {
+                format!("{}...", arg.name)
+            } else {
+                arg.name.clone()
+            };
+
+            if arg.required {
+                usage.push_str(&format!(" <{name}>"));
+            } else {
+                usage.push_str(&format!(" [{name}]"));
+            }
+        }
+
+        usage
+    }
+}
+
+/// Extension trait for [`App`] to handle prefix commands, see the [module](self) for more
+/// information.
+pub trait DiscordPrefixCommandAppExt {
+    /// Registers the command, and runs the system whenever it is used.
+    ///
+    /// Registering a command with the same name again adds the system to it.
+    fn add_prefix_command<M>(
+        &mut self,
+        command: PrefixCommand,
+        system: impl IntoSystem<In<PrefixInvoked>, (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl DiscordPrefixCommandAppExt for App {
+    fn add_prefix_command<M>(
+        &mut self,
+        command: PrefixCommand,
+        system: impl IntoSystem<In<PrefixInvoked>, (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        routing::add_command(self, command, system);
+        self
+    }
+}
@@ -0,0 +1,433 @@
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemId;
+use serenity::all::{CreateMessage, GuildId};
+use serenity::model::channel::Message as SMessage;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::PrefixCommand;
+use super::args::{ArgumentError, PrefixArgument, tokenize};
+use crate::DiscordSystems;
+use crate::checks::{
+    CheckContext, CommandChecksPlugin, CommandCooldowns, CommandOwners, CommandRejected,
+};
+use crate::messages::bot::DiscordMessage;
+use crate::messages::http::DiscordRequest;
+use crate::messages::{read_with_context, send_events_bot};
+
+/// Prefix of the commands, which can be changed per guild.
+///
+/// Defaults to `!`.
+#[derive(Resource, Debug, Clone)]
+pub struct CommandPrefixes {
+    default: String,
+    guilds: HashMap<GuildId, String>,
+}
+
+impl Default for CommandPrefixes {
+    fn default() -> Self {
+        Self::new("!")
+    }
+}
+
+impl CommandPrefixes {
+    /// Uses the prefix everywhere, unless a guild has its own.
+    pub fn new(default: impl Into<String>) -> Self {
+        Self {
+            default: default.into(),
+            guilds: HashMap::new(),
+        }
+    }
+
+    /// Returns the prefix used in the guild, or in DMs for `None`.
+    pub fn prefix(&self, guild_id: Option<GuildId>) -> &str {
+        guild_id
+            .and_then(|guild_id| self.guilds.get(&guild_id))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn set_default(&mut self, prefix: impl Into<String>) {
+        self.default = prefix.into();
+    }
+
+    pub fn set_guild_prefix(&mut self, guild_id: GuildId, prefix: impl Into<String>) {
+        self.guilds.insert(guild_id, prefix.into());
+    }
+
+    /// Goes back to the default prefix in the guild.
+    pub fn remove_guild_prefix(&mut self, guild_id: GuildId) {
+        self.guilds.remove(&guild_id);
+    }
+}
+
+/// Given to the systems of a prefix command whenever it is used.
+#[derive(Debug, Clone)]
+pub struct PrefixInvoked {
+    /// Command that was used.
+    pub command: Arc<PrefixCommand>,
+    /// Prefix the command was used with.
+    pub prefix: String,
+    /// Arguments in the order they were given, the rest argument being a single one.
+    pub args: Vec<String>,
+    pub message: SMessage,
+}
+
+impl PrefixInvoked {
+    /// Converts the argument, see [`PrefixArgument`] for the supported types.
+    pub fn arg<T: PrefixArgument>(&self, name: &str) -> Result<T, ArgumentError> {
+        self.optional_arg(name)?
+            .ok_or_else(|| ArgumentError::MissingArgument(name.to_string()))
+    }
+
+    /// Converts the argument, returning `None` if it wasn't given.
+    pub fn optional_arg<T: PrefixArgument>(&self, name: &str) -> Result<Option<T>, ArgumentError> {
+        let index = self
+            .command
+            .args
+            .iter()
+            .position(|arg| arg.name == name)
+            .ok_or_else(|| ArgumentError::UnknownArgument(name.to_string()))?;
+
+        let Some(value) = self.args.get(index) else {
+            return Ok(None);
+        };
+
+        T::from_arg(value)
+            .map(Some)
+            .ok_or_else(|| ArgumentError::InvalidArgument {
+                name: name.to_string(),
+                value: value.clone(),
+            })
+    }
+}
+
+type PrefixSystemId = SystemId<In<PrefixInvoked>>;
+
+/// The registered prefix commands.
+#[derive(Resource, Debug)]
+pub struct PrefixCommands {
+    commands: Vec<(Arc<PrefixCommand>, Vec<PrefixSystemId>)>,
+    /// Index of the commands by their name and aliases.
+    names: HashMap<String, usize>,
+    help_command: Option<String>,
+}
+
+impl Default for PrefixCommands {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            names: HashMap::new(),
+            help_command: Some("help".to_string()),
+        }
+    }
+}
+
+impl PrefixCommands {
+    /// Returns the command with the name or alias.
+    pub fn get(&self, name: &str) -> Option<&PrefixCommand> {
+        self.names
+            .get(&name.to_lowercase())
+            .map(|index| self.commands[*index].0.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PrefixCommand> {
+        self.commands.iter().map(|(command, _)| command.as_ref())
+    }
+
+    /// Name of the command answered with [`help`](Self::help), `help` by default.
+    pub fn help_command(&self) -> Option<&str> {
+        self.help_command.as_deref()
+    }
+
+    /// Sets the name of the help command, `None` disables it. A registered command with the
+    /// same name takes precedence.
+    pub fn set_help_command(&mut self, name: Option<String>) {
+        self.help_command = name.map(|name| name.to_lowercase());
+    }
+
+    /// Returns the usage and description of every command.
+    pub fn help(&self, prefix: &str) -> String {
+        self.iter()
+            .map(|command| {
+                if command.description.is_empty() {
+                    format!("`{}`", command.usage(prefix))
+                } else {
+                    format!("`{}` - {}", command.usage(prefix), command.description)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Parses the messages and dispatches the prefix commands, added by
+/// [`DiscordPrefixCommandAppExt`](super::DiscordPrefixCommandAppExt).
+struct PrefixCommandsPlugin;
+
+impl Plugin for PrefixCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CommandChecksPlugin>() {
+            app.add_plugins(CommandChecksPlugin);
+        }
+
+        read_with_context::<DiscordMessage>(app);
+
+        app.init_resource::<CommandPrefixes>()
+            .init_resource::<PrefixCommands>()
+            .add_systems(
+                Update,
+                dispatch_prefix_commands
+                    .after(send_events_bot)
+                    .in_set(DiscordSystems),
+            );
+    }
+}
+
+pub(crate) fn add_command(app: &mut App, command: PrefixCommand, system: PrefixSystemId) {
+    if !app.is_plugin_added::<PrefixCommandsPlugin>() {
+        app.add_plugins(PrefixCommandsPlugin);
+    }
+
+    let mut registry = app.world_mut().resource_mut::<PrefixCommands>();
+
+    if let Some(index) = registry
+        .commands
+        .iter()
+        .position(|(existing, _)| existing.name == command.name)
+    {
+        registry.commands[index].1.push(system);
+        return;
+    }
+
+    let index = registry.commands.len();
+    for (position, name) in std::iter::once(&command.name)
+        .chain(&command.aliases)
+        .enumerate()
+    {
+        let Some(existing) = registry.names.get(name).copied() else {
+            registry.names.insert(name.clone(), index);
+            continue;
+        };
+
+        // The name of a command takes precedence over the aliases of the others
+        let other = registry.commands[existing].0.clone();
+        if position == 0 {
+            warn!(
+                "The prefix command `{}` replaces the alias of `{}`",
+                command.name, other.name
+            );
+            registry.names.insert(name.clone(), index);
+        } else {
+            warn!(
+                "The alias `{}` of the prefix command `{}` is already used by `{}`",
+                name, command.name, other.name
+            );
+        }
+    }
+    registry.commands.push((Arc::new(command), vec![system]));
+}
+
+/// Splits the arguments of the command, returns `None` if there are too many or too few.
+///
+/// The last argument of a command with a rest argument is the rest of `content` as it was
+/// written, from the first argument that doesn't fit.
+fn split_args(
+    command: &PrefixCommand,
+    content: &str,
+    mut args: Vec<(usize, String)>,
+) -> Option<Vec<String>> {
+    let required = command.args.iter().filter(|arg| arg.required).count();
+    let rest = command.args.last().is_some_and(|arg| arg.rest);
+
+    if rest && args.len() > command.args.len() {
+        let (start, _) = args.split_off(command.args.len() - 1)[0];
+        args.push((start, content[start..].trim_end().to_string()));
+    }
+
+    (args.len() >= required && args.len() <= command.args.len())
+        .then(|| args.into_iter().map(|(_, arg)| arg).collect())
+}
+
+fn reply(message: &SMessage, content: String) -> DiscordRequest {
+    DiscordRequest::SendMessage {
+        channel_id: message.channel_id,
+        message: CreateMessage::new()
+            .content(content)
+            .reference_message(message),
+    }
+}
+
+/// Returns what the checks know about the message.
+fn check_context(event: &DiscordMessage) -> CheckContext {
+    let message = &event.new_message;
+
+    #[cfg(feature = "bot_cache")]
+    let permissions = message.author_permissions(&event.ctx.cache);
+    #[cfg(not(feature = "bot_cache"))]
+    let permissions = None;
+
+    CheckContext {
+        user_id: message.author.id,
+        channel_id: message.channel_id,
+        guild_id: message.guild_id,
+        permissions,
+        roles: message
+            .member
+            .as_ref()
+            .map(|member| member.roles.clone())
+            .unwrap_or_default(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dispatch_prefix_commands(
+    mut messages: MessageReader<DiscordMessage>,
+    mut requests: MessageWriter<DiscordRequest>,
+    mut rejected: MessageWriter<CommandRejected>,
+    prefixes: Res<CommandPrefixes>,
+    registry: Res<PrefixCommands>,
+    owners: Res<CommandOwners>,
+    mut cooldowns: ResMut<CommandCooldowns>,
+    mut commands: Commands,
+) {
+    for event in messages.read() {
+        let message = &event.new_message;
+
+        if message.author.bot {
+            continue;
+        }
+
+        let prefix = prefixes.prefix(message.guild_id);
+        let Some(content) = message.content.strip_prefix(prefix) else {
+            continue;
+        };
+
+        let mut args = tokenize(content);
+        if args.is_empty() {
+            continue;
+        }
+        let (_, name) = args.remove(0);
+        let name = name.to_lowercase();
+
+        let Some(index) = registry.names.get(&name).copied() else {
+            if registry.help_command.as_ref() == Some(&name) {
+                requests.write(reply(message, registry.help(prefix)));
+            }
+            continue;
+        };
+
+        let (command, systems) = &registry.commands[index];
+
+        let Some(args) = split_args(command, content, args) else {
+            requests.write(reply(
+                message,
+                format!("Usage: `{}`", command.usage(prefix)),
+            ));
+            continue;
+        };
+
+        if let Some(checks) = &command.checks {
+            let context = check_context(event);
+
+            match checks.evaluate(&command.name, &context, &owners, &cooldowns) {
+                Ok(started) => cooldowns.start(started),
+                Err(reason) => {
+                    if checks.responds() {
+                        requests.write(reply(message, reason.to_string()));
+                    }
+                    rejected.write(CommandRejected::new(&command.name, &context, reason));
+                    continue;
+                }
+            }
+        }
+
+        let invoked = PrefixInvoked {
+            command: command.clone(),
+            prefix: prefix.to_string(),
+            args,
+            message: message.clone(),
+        };
+
+        for system in systems {
+            commands.run_system_with(*system, invoked.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(command: &PrefixCommand, content: &str) -> Option<Vec<String>> {
+        split_args(command, content, tokenize(content))
+    }
+
+    #[test]
+    fn rest_argument_keeps_the_original_text() {
+        let command = PrefixCommand::new("mute")
+            .arg("player", "")
+            .rest_arg("reason", "");
+
+        assert_eq!(
+            split(&command, r#"@player  spamming   "a lot" "#),
+            Some(vec![
+                "@player".to_string(),
+                r#"spamming   "a lot""#.to_string()
+            ])
+        );
+        assert_eq!(
+            split(&command, r#"@player "a lot""#),
+            Some(vec!["@player".to_string(), "a lot".to_string()])
+        );
+    }
+
+    #[test]
+    fn wrong_argument_counts_are_rejected() {
+        let command = PrefixCommand::new("ban")
+            .arg("player", "")
+            .optional_arg("days", "");
+
+        assert_eq!(split(&command, ""), None);
+        assert_eq!(split(&command, "a 1 extra"), None);
+        assert_eq!(split(&command, "a"), Some(vec!["a".to_string()]));
+    }
+
+    #[test]
+    #[should_panic(expected = "comes after its rest argument")]
+    fn arguments_after_the_rest_one_panic() {
+        let _ = PrefixCommand::new("mute")
+            .rest_arg("reason", "")
+            .optional_arg("days", "");
+    }
+
+    #[test]
+    #[should_panic(expected = "comes after an optional one")]
+    fn required_arguments_after_optional_ones_panic() {
+        let _ = PrefixCommand::new("ban")
+            .optional_arg("days", "")
+            .arg("player", "");
+    }
+
+    fn noop(_: In<PrefixInvoked>) {}
+
+    #[test]
+    fn names_take_precedence_over_aliases() {
+        let mut app = App::new();
+        let first = app.register_system(noop);
+        let second = app.register_system(noop);
+        let third = app.register_system(noop);
+
+        add_command(&mut app, PrefixCommand::new("ban").alias("kick"), first);
+        add_command(&mut app, PrefixCommand::new("kick").alias("ban"), second);
+        add_command(&mut app, PrefixCommand::new("kick"), third);
+
+        let registry = app.world().resource::<PrefixCommands>();
+        assert_eq!(registry.commands.len(), 2);
+        assert_eq!(registry.commands[0].1, [first]);
+        assert_eq!(registry.commands[1].1, [second, third]);
+        assert_eq!(registry.get("ban").unwrap().name, "ban");
+        assert_eq!(registry.get("kick").unwrap().name, "kick");
+    }
+}
@@ -0,0 +1,282 @@
+//! Recording of the gateway events, and replaying them without a connection to discord.
+//!
+//! With [`DiscordBotConfig::record_events`], every gateway event received by the bot is written
+//! to a file as a line of JSON, see [`RecordedEntry`]. [`DiscordReplayPlugin`] reads such a file
+//! and writes its events as the same bevy messages, at their original pace or faster, which
+//! makes it possible to reproduce what happened in production offline.
+//!
+//! The stage updates of the shards aren't recorded, as they don't come from discord.
+//!
+//! The [`Context`] of the events isn't recorded. The replayed events get a context that isn't
+//! connected to discord, messages sent to its shard are dropped and its http client uses the
+//! token and base URL of the config given to the plugin.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordReplayPlugin;
+//! use bevy_discord::messages::bot::DiscordMessage;
+//!
+//! fn log_messages(mut messages: MessageReader<DiscordMessage>) {
+//!     for message in messages.read() {
+//!         info!("{}: {}", message.new_message.author.name, message.new_message.content);
+//!     }
+//! }
+//!
+//! // Recorded with `DiscordBotConfig::default().record_events("events.jsonl")`
+//! App::new()
+//!     .add_plugins(DiscordReplayPlugin::new("events.jsonl").speed(10.0))
+//!     .add_systems(Update, log_messages)
+//!     .run();
+//! ```
+//!
+//! [`DiscordBotConfig::record_events`]: crate::config::DiscordBotConfig::record_events
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use serenity::all::*;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::time::Instant;
+use tracing::{error, warn};
+
+use crate::DiscordBotPlugin;
+use crate::bot::handle::EventSender;
+use crate::bot::offline::offline_context;
+use crate::config::DiscordBotConfig;
+use crate::messages::MessageCollectionBot;
+use crate::messages::bot::*;
+
+/// A line of a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEntry {
+    /// When the event was received, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+    /// Shard that received the event.
+    pub shard_id: u32,
+    pub event: DiscordEventKind,
+}
+
+/// Writes the gateway events to a file, see the [module](self) for more information.
+///
+/// The events are written by a dedicated thread, so recording them never blocks the event
+/// handler. The thread stops once the recorder is dropped and every event has been written.
+pub(crate) struct EventRecorder(flume::Sender<RecordedEntry>);
+
+impl EventRecorder {
+    /// Creates the file, overwriting it if it exists.
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let mut file = LineWriter::new(File::create(path)?);
+        let (tx, rx) = flume::unbounded::<RecordedEntry>();
+
+        std::thread::Builder::new()
+            .name("discord-recorder".to_string())
+            .spawn(move || {
+                for entry in rx {
+                    let result = serde_json::to_string(&entry)
+                        .map_err(io::Error::from)
+                        .and_then(|line| writeln!(file, "{line}"));
+
+                    if let Err(err) = result {
+                        error!("Unable to record the event: {}", err);
+                    }
+                }
+            })?;
+
+        Ok(Self(tx))
+    }
+
+    /// Queues the gateway event to be written, in the order of the calls.
+    pub(crate) fn record(
+        &self,
+        shard_id: ShardId,
+        event: &DiscordEventKind,
+        received_at: SystemTime,
+    ) {
+        let entry = RecordedEntry {
+            timestamp: received_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            shard_id: shard_id.0,
+            event: event.clone(),
+        };
+
+        if self.0.send(entry).is_err() {
+            error!("Unable to record the event, the recorder has stopped");
+        }
+    }
+}
+
+/// Replays the events of a recording instead of connecting to discord, see the
+/// [module](self) for more information.
+///
+/// It adds [`DiscordBotPlugin`], so everything that works with it works with the replayed
+/// events. A [`BotDisconnectedMessage`] is written once all the events have been replayed.
+#[derive(Debug, Clone)]
+pub struct DiscordReplayPlugin {
+    replay: EventReplay,
+    config: DiscordBotConfig,
+}
+
+/// Recording replayed by [`DiscordReplayPlugin`].
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct EventReplay {
+    path: PathBuf,
+    speed: f64,
+}
+
+impl DiscordReplayPlugin {
+    /// Replays the file at its original pace.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            replay: EventReplay {
+                path: path.into(),
+                speed: 1.0,
+            },
+            config: DiscordBotConfig::default(),
+        }
+    }
+
+    /// Multiplies the pace of the replay, e.g. `2.0` replays twice as fast and
+    /// [`f64::INFINITY`] replays all the events right away.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the speed isn't positive.
+    pub fn speed(mut self, speed: f64) -> Self {
+        assert!(speed > 0.0, "the speed of the replay has to be positive");
+        self.replay.speed = speed;
+        self
+    }
+
+    /// Sets the config of [`DiscordBotPlugin`]. The gateway settings aren't used, and the token
+    /// and base URL are only used by the http client.
+    pub fn config(mut self, config: DiscordBotConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl Plugin for DiscordReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.replay.clone())
+            .add_plugins(DiscordBotPlugin::new(self.config.clone()));
+    }
+}
+
+/// Sends the events of the recording to bevy, waiting between them as they were received.
+pub(crate) async fn replay_events(tx: EventSender, replay: EventReplay, http: Http) {
+    let graceful = match run_replay(&tx, &replay, http).await {
+        Ok(()) => true,
+        Err(err) => {
+            error!("Unable to replay {}: {}", replay.path.display(), err);
+            false
+        }
+    };
+
+    send(
+        &tx,
+        MessageCollectionBot::BotDisconnectedMessage(BotDisconnectedMessage { graceful }),
+    )
+    .await;
+}
+
+async fn run_replay(tx: &EventSender, replay: &EventReplay, http: Http) -> serenity::Result<()> {
+    let mut lines = BufReader::new(tokio::fs::File::open(&replay.path).await?).lines();
+    let ctx = offline_context(Arc::new(http)).await?;
+    let started = Instant::now();
+    let mut first_timestamp = None;
+    let mut index = 0;
+
+    while let Some(line) = lines.next_line().await? {
+        index += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry: RecordedEntry = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Skipping line {} of the recording: {}", index, err);
+                continue;
+            }
+        };
+
+        let first_timestamp = *first_timestamp.get_or_insert(entry.timestamp);
+        let elapsed = Duration::from_millis(entry.timestamp.saturating_sub(first_timestamp));
+        let deadline = Duration::try_from_secs_f64(elapsed.as_secs_f64() / replay.speed)
+            .ok()
+            .and_then(|delay| started.checked_add(delay));
+
+        match deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            // The event would be replayed after the end of times
+            None => std::future::pending().await,
+        }
+
+        let mut ctx = ctx.clone();
+        ctx.shard_id = ShardId(entry.shard_id);
+        send(tx, entry.event.into_message(ctx)).await;
+    }
+
+    Ok(())
+}
+
+async fn send(tx: &EventSender, message: MessageCollectionBot) {
+    if tx.send_async(message).await.is_err() {
+        error!("Unable to send event to the channel")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_events_are_written_in_order() {
+        let path = std::env::temp_dir().join(format!("recording-{}.jsonl", std::process::id()));
+        let recorder = EventRecorder::create(&path).unwrap();
+
+        for id in 1..=20 {
+            let event = DiscordEventKind::GuildDeleteMessage {
+                incomplete: serde_json::from_value(
+                    serde_json::json!({ "id": id.to_string(), "unavailable": false }),
+                )
+                .unwrap(),
+                full: None,
+            };
+            recorder.record(ShardId(0), &event, SystemTime::now());
+        }
+        drop(recorder);
+
+        let mut lines = Vec::new();
+        for _ in 0..100 {
+            lines = std::fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<RecordedEntry>(line).unwrap())
+                .collect();
+            if lines.len() == 20 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        let ids: Vec<_> = lines
+            .into_iter()
+            .map(|entry| match entry.event {
+                DiscordEventKind::GuildDeleteMessage { incomplete, .. } => incomplete.id.get(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(ids, (1..=20).collect::<Vec<_>>());
+    }
+}
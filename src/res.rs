@@ -1,8 +1,11 @@
 //! Resources for managing Discord HTTP client and Rich Presence functionality.
 //!
 //! This module provides two main resources:
-//! - `DiscordHttpResource`: For handling Discord HTTP client operations
+//! - `DiscordHttpResource`: For handling Discord HTTP client operations, whose requests can be
+//!   sent elsewhere with `DiscordHttpBaseUrl`
 //! - `DiscordRichPresenceRes`: For managing Discord Rich Presence integration
+//!
+//! Along with [`MessageQueueStats`], which reports how the queue between Discord and Bevy is doing.
 
 use bevy_ecs::prelude::*;
 use std::sync::Arc;
@@ -35,6 +38,15 @@ use std::sync::Arc;
 pub struct DiscordHttpResource {
     /// Arc-wrapped Serenity HTTP client for Discord API operations.
     pub http: Arc<serenity::all::Http>,
+    /// Carries the results of [`request`](Self::request) back to the app, set once the resource
+    /// is used by the app.
+    pub(crate) completed: Option<flume::Sender<crate::messages::http::DiscordRequestCompleted>>,
+    /// Interactions watched by the bot client this resource was created for, so that responses
+    /// to interactions deferred by the watchdog are sent as follow-ups or edits.
+    #[cfg(feature = "bot")]
+    pub(crate) watched: Option<crate::bot::watchdog::WatchedInteractions>,
+    /// Requests and tasks spawned through this resource, shared with the app it is used by.
+    pub(crate) pending: crate::runtime::PendingRequests,
 }
 
 #[cfg(feature = "http")]
@@ -45,7 +57,13 @@ impl DiscordHttpResource {
     ///
     /// * `http` - An Arc-wrapped Serenity HTTP client
     pub fn new(http: Arc<serenity::all::Http>) -> Self {
-        Self { http }
+        Self {
+            http,
+            completed: None,
+            #[cfg(feature = "bot")]
+            watched: None,
+            pending: Default::default(),
+        }
     }
 
     /// Returns a cloned Arc reference to the HTTP client.
@@ -63,8 +81,74 @@ impl DiscordHttpResource {
     pub fn client_ref(&self) -> &serenity::all::Http {
         &self.http
     }
+
+    /// Sends the request to Discord, and returns the id of the
+    /// [`DiscordRequestCompleted`](crate::messages::http::DiscordRequestCompleted) that is
+    /// dispatched once it has finished.
+    ///
+    /// Unlike writing a [`DiscordRequest`](crate::messages::http::DiscordRequest) message, the
+    /// request is sent right away. The result is dispatched to the app the resource was inserted
+    /// in, a request sent before the resource was inserted only logs its failure.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use bevy::prelude::*;
+    /// use bevy_discord::messages::http::{DiscordRequest, DiscordRequestCompleted, DiscordRequestId};
+    /// use bevy_discord::res::DiscordHttpResource;
+    /// use bevy_discord::serenity::all::{ChannelId, CreateMessage};
+    ///
+    /// #[derive(Resource)]
+    /// struct Announcement(DiscordRequestId);
+    ///
+    /// fn announce(http: Res<DiscordHttpResource>, mut commands: Commands) {
+    ///     let id = http.request(DiscordRequest::SendMessage {
+    ///         channel_id: ChannelId::new(1234),
+    ///         message: CreateMessage::new().content("Server started"),
+    ///     });
+    ///     commands.insert_resource(Announcement(id));
+    /// }
+    ///
+    /// fn announced(
+    ///     mut completed: MessageReader<DiscordRequestCompleted>,
+    ///     announcement: Res<Announcement>,
+    /// ) {
+    ///     for completed in completed.read().filter(|completed| completed.id == announcement.0) {
+    ///         info!("Announcement sent: {:?}", completed.result.is_ok());
+    ///     }
+    /// }
+    /// ```
+    pub fn request(
+        &self,
+        request: crate::messages::http::DiscordRequest,
+    ) -> crate::messages::http::DiscordRequestId {
+        crate::http::request::spawn_request(self.clone(), request)
+    }
 }
 
+/// Sends the requests of [`DiscordHttpPlugin`](crate::DiscordHttpPlugin) to this URL instead of
+/// `https://discord.com`, e.g. to a proxy or a local stand-in for discord. The paths are kept,
+/// e.g. `/api/v10/channels/1/messages`.
+///
+/// The requests aren't rate limited by the client then, the proxy is expected to do it. It has
+/// to be inserted before the plugin is added.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_discord::DiscordHttpPlugin;
+/// use bevy_discord::res::DiscordHttpBaseUrl;
+///
+/// App::new()
+///     .insert_resource(DiscordHttpBaseUrl("http://localhost:8080".to_string()))
+///     .add_plugins(DiscordHttpPlugin::new("token".to_string()));
+/// ```
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct DiscordHttpBaseUrl(pub String);
+
 /// A global resource for managing Discord Rich Presence functionality.
 ///
 /// This resource maintains the bot's Rich Presence state and provides access
@@ -119,3 +203,67 @@ impl DiscordRichPresenceRes {
         &self.discord
     }
 }
+
+/// Statistics about the queue that carries Discord events into Bevy messages, such as its depth
+/// and the number of dropped events.
+///
+/// The generic parameter is the plugin that owns the queue, i.e.
+/// `MessageQueueStats<DiscordBotPlugin>` or `MessageQueueStats<DiscordRichPresencePlugin>`.
+/// The resource is updated every frame by the system that forwards the events, see
+/// [`DiscordBotConfig::max_messages_per_frame`](crate::config::DiscordBotConfig::max_messages_per_frame)
+/// for limiting how much is forwarded in a single frame.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_discord::DiscordBotPlugin;
+/// use bevy_discord::res::MessageQueueStats;
+///
+/// fn report_backlog(stats: Res<MessageQueueStats<DiscordBotPlugin>>) {
+///     if stats.pending > 0 {
+///         warn!("{} discord events are waiting for the next frame", stats.pending);
+///     }
+/// }
+/// ```
+#[cfg(any(feature = "bot", feature = "rich_presence"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bot", feature = "rich_presence"))))]
+#[derive(Resource, Debug)]
+pub struct MessageQueueStats<T> {
+    /// Number of events forwarded as Bevy messages during the last frame.
+    pub forwarded: usize,
+    /// Number of events left in the queue after the last frame.
+    pub pending: usize,
+    /// Capacity of the queue, `None` if the queue is unbounded.
+    pub capacity: Option<usize>,
+    /// Total number of events dropped because the queue was full, see
+    /// [`OverflowPolicy`](crate::config::OverflowPolicy).
+    pub dropped: u64,
+    _marker: std::marker::PhantomData<fn() -> T>,
+}
+
+#[cfg(any(feature = "bot", feature = "rich_presence"))]
+impl<T> Default for MessageQueueStats<T> {
+    fn default() -> Self {
+        Self {
+            forwarded: 0,
+            pending: 0,
+            capacity: None,
+            dropped: 0,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[cfg(any(feature = "bot", feature = "rich_presence"))]
+impl<T> Clone for MessageQueueStats<T> {
+    fn clone(&self) -> Self {
+        Self {
+            forwarded: self.forwarded,
+            pending: self.pending,
+            capacity: self.capacity,
+            dropped: self.dropped,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
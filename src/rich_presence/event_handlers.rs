@@ -1,25 +1,35 @@
+use crate::channel::ChannelSender;
 use crate::common::{send_message, send_message_tuple};
+use crate::error::RichPresenceError;
 use crate::messages::{MessageCollectionRichPresence, rich_presence::*};
 use async_trait::async_trait;
 use discord_sdk::activity::events::{InviteEvent, JoinRequestEvent, SecretEvent};
 use discord_sdk::overlay::events::UpdateEvent as OverlayUpdateEvent;
 use discord_sdk::user::events::{ConnectEvent, UpdateEvent};
 use discord_sdk::{DiscordHandler, DiscordMsg, Event};
-use flume::Sender;
 use tracing::error;
 
 pub struct MessageHandler {
-    pub tx: Sender<MessageCollectionRichPresence>,
+    pub tx: ChannelSender<MessageCollectionRichPresence>,
 }
 
 #[async_trait]
 impl DiscordHandler for MessageHandler {
     async fn on_message(&self, msg: DiscordMsg) {
         match msg {
-            DiscordMsg::Error(err) => error!(
-                "Got an error from `discord-sdk` i.e. feature `rich-presence` in `bevy-discord`. Error => {:?}",
-                err
-            ),
+            DiscordMsg::Error(err) => {
+                error!(
+                    "Got an error from `discord-sdk` i.e. feature `rich-presence` in `bevy-discord`. Error => {:?}",
+                    err
+                );
+
+                let error = RichPresenceError::from(err);
+                send_message!(
+                    self,
+                    MessageCollectionRichPresence,
+                    RichPresenceErrorMessage { error }
+                )
+            }
             DiscordMsg::Event(event) => match event {
                 Event::Ready(ConnectEvent { version, user, .. }) => {
                     send_message!(
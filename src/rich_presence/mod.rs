@@ -10,13 +10,19 @@
 mod event_handlers;
 
 use crate::DiscordSystems;
+use crate::channel::{ChannelRes, DrainBudget};
 use crate::messages::{MessageCollectionRichPresence, rich_presence::*, send_events_rich_presence};
+use crate::res::MessageQueueStats;
 use crate::rich_presence::event_handlers::MessageHandler;
-use crate::{channel::ChannelRes, runtime::tokio_runtime};
+use crate::runtime::tokio_runtime;
+use crate::state::RichPresenceConnectionState;
 use bevy_app::{App, Plugin, Startup, Update};
 use bevy_ecs::prelude::*;
+use bevy_state::app::{AppExtStates, StatesPlugin};
+use bevy_state::state::NextState;
 use discord_sdk::Discord;
 use std::sync::Arc;
+use tracing::error;
 
 /// A plugin for integrating Discord Rich Presence with the Bevy game engine. You
 /// can do anything that you can do with [Game SDK](https://discord.com/developers/docs/developer-tools/game-sdk).
@@ -39,9 +45,22 @@ impl DiscordRichPresencePlugin {
 
 impl Plugin for DiscordRichPresencePlugin {
     fn build(&self, app: &mut App) {
-        let (tx, rx) = flume::unbounded::<MessageCollectionRichPresence>();
-        let channel_res = ChannelRes { tx, rx };
-        app.insert_resource(channel_res);
+        let budget = DrainBudget {
+            max_messages: self.0.max_messages_per_frame,
+            max_time: self.0.max_time_per_frame,
+        };
+        let channel_res = ChannelRes::<MessageCollectionRichPresence>::new(
+            self.0.channel_capacity,
+            self.0.overflow_policy.clone(),
+            budget,
+        );
+        app.insert_resource(channel_res)
+            .init_resource::<MessageQueueStats<DiscordRichPresencePlugin>>();
+
+        if !app.is_plugin_added::<StatesPlugin>() {
+            app.add_plugins(StatesPlugin);
+        }
+        app.init_state::<RichPresenceConnectionState>();
 
         app.insert_resource(self.0.clone())
             .add_message::<ErrorMessage>()
@@ -54,8 +73,14 @@ impl Plugin for DiscordRichPresencePlugin {
             .add_message::<ActivityInviteMessage>()
             .add_message::<OverlayUpdateMessage>()
             .add_message::<RelationshipUpdateMessage>()
+            .add_message::<RichPresenceErrorMessage>()
             .add_systems(Startup, setup_rich_presence)
-            .add_systems(Update, send_events_rich_presence.in_set(DiscordSystems));
+            .add_systems(
+                Update,
+                (send_events_rich_presence, update_connection_state)
+                    .chain()
+                    .in_set(DiscordSystems),
+            );
     }
 }
 
@@ -63,21 +88,45 @@ fn setup_rich_presence(
     mut commands: Commands,
     discord_rich_presence_config: Res<crate::config::DiscordRichPresenceConfig>,
     channel_res: Res<ChannelRes<MessageCollectionRichPresence>>,
+    mut errors: MessageWriter<RichPresenceErrorMessage>,
+    mut next_state: ResMut<NextState<RichPresenceConnectionState>>,
 ) {
     let tx = channel_res.tx.clone();
     let event_handler = Box::new(MessageHandler { tx });
 
     let discord_rich_presence_config = discord_rich_presence_config.clone();
-    let discord_res = || {
-        commands.insert_resource(crate::res::DiscordRichPresenceRes::new(Arc::new(
-            Discord::new(
-                discord_rich_presence_config.app,
-                discord_rich_presence_config.subscriptions,
-                event_handler,
-            )
-            .expect("Failed to create a Discord Rich Presence Client"),
-        )))
-    };
+    let discord = tokio_runtime().block_on(async move {
+        Discord::new(
+            discord_rich_presence_config.app,
+            discord_rich_presence_config.subscriptions,
+            event_handler,
+        )
+    });
+
+    match discord {
+        Ok(discord) => {
+            commands.insert_resource(crate::res::DiscordRichPresenceRes::new(Arc::new(discord)));
+            next_state.set(RichPresenceConnectionState::Connecting);
+        }
+        Err(err) => {
+            let error = crate::error::RichPresenceError::from(err);
+            error!("Failed to create a Discord Rich Presence Client: {}", error);
+            errors.write(RichPresenceErrorMessage { error });
+            next_state.set(RichPresenceConnectionState::Failed);
+        }
+    }
+}
 
-    tokio_runtime().block_on(async move { discord_res() });
+fn update_connection_state(
+    mut ready: MessageReader<RpReadyMessage>,
+    mut disconnected: MessageReader<DisconnectedMessage>,
+    mut next_state: ResMut<NextState<RichPresenceConnectionState>>,
+) {
+    if ready.read().count() > 0 {
+        next_state.set(RichPresenceConnectionState::Ready);
+    }
+
+    if disconnected.read().count() > 0 {
+        next_state.set(RichPresenceConnectionState::Disconnected);
+    }
 }
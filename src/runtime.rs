@@ -1,8 +1,91 @@
+use std::future::Future;
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::runtime::Runtime;
+#[cfg(feature = "http")]
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Number of futures spawned with [`spawn_tracked`] that haven't finished yet.
+static PENDING_TRACKED: AtomicUsize = AtomicUsize::new(0);
 
 /// Tokio runtime, use this if you want to use async code inside bevy systems
 pub fn tokio_runtime() -> &'static Runtime {
     static RUNTIME: OnceLock<Runtime> = OnceLock::new();
     RUNTIME.get_or_init(|| Runtime::new().expect("Setting up tokio runtime needs to succeed."))
 }
+
+/// Spawns a future on [`tokio_runtime`] that is waited for before the app exits.
+///
+/// Use this instead of `tokio_runtime().spawn` for outbound work such as sending a message, so
+/// that it isn't cut off when the app exits. `DiscordBotPlugin` waits for these futures, up to
+/// the configured shutdown timeout, once the app has exited.
+pub fn spawn_tracked<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    // Decrements the counter even if the future panics or is aborted
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            PENDING_TRACKED.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    PENDING_TRACKED.fetch_add(1, Ordering::SeqCst);
+    let guard = Guard;
+
+    tokio_runtime().spawn(async move {
+        let _guard = guard;
+        future.await
+    })
+}
+
+/// Waits until every future spawned with [`spawn_tracked`] has finished.
+#[cfg(feature = "bot")]
+pub(crate) async fn tracked_finished() {
+    while PENDING_TRACKED.load(Ordering::SeqCst) > 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+/// Counts the futures an app has spawned with [`PendingRequests::spawn`] that haven't finished
+/// yet, so that its requests can be waited for without waiting for other apps.
+#[cfg(feature = "http")]
+#[derive(bevy_ecs::resource::Resource, Debug, Clone, Default)]
+pub(crate) struct PendingRequests(watch::Sender<usize>);
+
+#[cfg(feature = "http")]
+impl PendingRequests {
+    /// Spawns the future with [`spawn_tracked`], and counts it until it has finished.
+    pub(crate) fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        // Decrements the counter even if the future panics or is aborted
+        struct Guard(watch::Sender<usize>);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.0.send_modify(|pending| *pending -= 1);
+            }
+        }
+
+        self.0.send_modify(|pending| *pending += 1);
+        let guard = Guard(self.0.clone());
+
+        spawn_tracked(async move {
+            let _guard = guard;
+            future.await
+        })
+    }
+
+    /// Waits until every future spawned with [`spawn`](Self::spawn) has finished.
+    #[cfg(feature = "testing")]
+    pub(crate) async fn finished(&self) {
+        let _ = self.0.subscribe().wait_for(|pending| *pending == 0).await;
+    }
+}
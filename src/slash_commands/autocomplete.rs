@@ -0,0 +1,218 @@
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemId;
+use serde_json::Value;
+use serenity::all::{
+    AutocompleteChoice, CommandInteraction, CreateAutocompleteResponse, CreateInteractionResponse,
+    Interaction, User,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+use super::routing::{normalize_path, resolve};
+use crate::DiscordSystems;
+use crate::messages::bot::InteractionCreateMessage;
+use crate::messages::http::DiscordRequest;
+use crate::messages::{read_with_context, send_events_bot};
+
+/// Discord doesn't accept more suggestions than this.
+const MAX_SUGGESTIONS: usize = 25;
+
+/// Given to the provider registered with
+/// [`DiscordCommandAppExt::add_discord_autocomplete`](super::DiscordCommandAppExt::add_discord_autocomplete)
+/// whenever the user types in the option.
+#[derive(Debug, Clone)]
+pub struct AutocompleteQuery {
+    /// Name of the command followed by its subcommand group and subcommand, e.g. `admin ban`.
+    pub path: String,
+    /// Name of the option the user is typing in.
+    pub option: String,
+    /// What the user has typed so far.
+    pub input: String,
+    pub interaction: CommandInteraction,
+    /// User that is typing.
+    pub user: User,
+}
+
+/// A value suggested by an autocomplete provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutocompleteSuggestion {
+    /// Shown to the user, and matched against their input.
+    pub name: String,
+    /// Sent as the value of the option once the suggestion is picked.
+    pub value: Value,
+}
+
+impl AutocompleteSuggestion {
+    pub fn new(name: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Suggests the name as the value of the option.
+impl<S: Into<String>> From<S> for AutocompleteSuggestion {
+    fn from(name: S) -> Self {
+        let name = name.into();
+        Self {
+            value: Value::String(name.clone()),
+            name,
+        }
+    }
+}
+
+type ProviderId = SystemId<In<AutocompleteQuery>, Vec<AutocompleteSuggestion>>;
+
+/// Providers by the path of the command and the name of the option.
+#[derive(Resource, Default)]
+struct AutocompleteProviders(HashMap<(String, String), ProviderId>);
+
+/// Answers the autocomplete interactions, added by
+/// [`DiscordCommandAppExt::add_discord_autocomplete`](super::DiscordCommandAppExt::add_discord_autocomplete).
+struct AutocompletePlugin;
+
+impl Plugin for AutocompletePlugin {
+    fn build(&self, app: &mut App) {
+        read_with_context::<InteractionCreateMessage>(app);
+
+        app.init_resource::<AutocompleteProviders>().add_systems(
+            Update,
+            answer_autocompletes
+                .after(send_events_bot)
+                .in_set(DiscordSystems),
+        );
+    }
+}
+
+pub(crate) fn add_provider(app: &mut App, path: &str, option: &str, provider: ProviderId) {
+    if !app.is_plugin_added::<AutocompletePlugin>() {
+        app.add_plugins(AutocompletePlugin);
+    }
+
+    app.world_mut()
+        .resource_mut::<AutocompleteProviders>()
+        .0
+        .insert((normalize_path(path), option.to_string()), provider);
+}
+
+/// Keeps the suggestions whose name contains the input, the ones starting with it first.
+fn filter_suggestions(
+    suggestions: Vec<AutocompleteSuggestion>,
+    input: &str,
+) -> Vec<AutocompleteChoice> {
+    let input = input.to_lowercase();
+
+    let mut matching: Vec<_> = suggestions
+        .into_iter()
+        .filter_map(|suggestion| {
+            let position = suggestion.name.to_lowercase().find(&input)?;
+            Some((position != 0, suggestion))
+        })
+        .collect();
+    // Stable, so the order of the provider is kept otherwise
+    matching.sort_by_key(|(not_prefix, _)| *not_prefix);
+
+    matching
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, suggestion)| AutocompleteChoice::new(suggestion.name, suggestion.value))
+        .collect()
+}
+
+fn answer_autocompletes(
+    mut interactions: MessageReader<InteractionCreateMessage>,
+    providers: Res<AutocompleteProviders>,
+    mut commands: Commands,
+) {
+    for event in interactions.read() {
+        let Interaction::Autocomplete(interaction) = &event.interaction else {
+            continue;
+        };
+
+        let Some(focused) = interaction.data.autocomplete() else {
+            continue;
+        };
+
+        let (path, _) = resolve(interaction);
+        let key = (path, focused.name.to_string());
+        let Some(provider) = providers.0.get(&key).copied() else {
+            continue;
+        };
+
+        let query = AutocompleteQuery {
+            path: key.0,
+            option: key.1,
+            input: focused.value.to_string(),
+            interaction: interaction.clone(),
+            user: interaction.user.clone(),
+        };
+
+        commands.queue(move |world: &mut World| {
+            let input = query.input.clone();
+            let interaction_id = query.interaction.id;
+            let token = query.interaction.token.clone();
+
+            let suggestions = match world.run_system_with(provider, query) {
+                Ok(suggestions) => suggestions,
+                Err(err) => {
+                    error!("Unable to run the autocomplete provider: {}", err);
+                    return;
+                }
+            };
+
+            let response = CreateAutocompleteResponse::new()
+                .set_choices(filter_suggestions(suggestions, &input));
+            world.write_message(DiscordRequest::RespondInteraction {
+                interaction_id,
+                token,
+                response: CreateInteractionResponse::Autocomplete(response),
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(suggestions: &[&str], input: &str) -> Vec<String> {
+        let suggestions = suggestions.iter().copied().map(Into::into).collect();
+
+        filter_suggestions(suggestions, input)
+            .into_iter()
+            .map(|choice| {
+                let choice = serde_json::to_value(choice).unwrap();
+                choice["name"].as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn suggestions_starting_with_the_input_come_first() {
+        assert_eq!(
+            names(&["Red apple", "Apricot", "Pineapple", "Apple pie"], "ap"),
+            ["Apricot", "Apple pie", "Red apple", "Pineapple"]
+        );
+    }
+
+    #[test]
+    fn suggestions_are_matched_case_insensitively() {
+        assert_eq!(
+            names(&["Berlin", "BERN", "Oslo"], "bEr"),
+            ["Berlin", "BERN"]
+        );
+        assert!(names(&["Oslo"], "x").is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_capped() {
+        let suggestions: Vec<_> = (0..40).map(|i| format!("item {i}")).collect();
+        let suggestions: Vec<_> = suggestions.iter().map(String::as_str).collect();
+
+        let names = names(&suggestions, "");
+        assert_eq!(names.len(), MAX_SUGGESTIONS);
+        assert_eq!(names[0], "item 0");
+    }
+}
@@ -0,0 +1,205 @@
+//! Application commands, i.e. slash commands and context menu commands.
+//!
+//! Register the commands of your bot with [`DiscordCommandAppExt::add_discord_command`], and they
+//! are synced with Discord once the bot is ready. Only the commands that changed are created,
+//! updated or deleted, and the outcome is reported as a
+//! [`CommandSyncMessage`](crate::messages::bot::CommandSyncMessage).
+//!
+//! Handle them with [`DiscordCommandAppExt::add_discord_command_system`] or
+//! [`DiscordCommandAppExt::add_discord_command_observer`], which receive a [`CommandInvoked`]
+//! for every use of the command. Commands without a handler are reported as
+//! [`UnhandledCommandMessage`](crate::messages::bot::UnhandledCommandMessage).
+//!
+//! Options with autocomplete enabled are answered by the providers registered with
+//! [`DiscordCommandAppExt::add_discord_autocomplete`].
+//!
+//! Alternatively, describe a command with a struct deriving [`DiscordCommand`] and register it
+//! with [`DiscordCommandAppExt::add_typed_discord_command`]. It is then both registered and
+//! handled, and every use is dispatched as a [`ParsedCommand`] with the options already parsed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordBotPlugin;
+//! use bevy_discord::config::DiscordBotConfig;
+//! use bevy_discord::serenity::all::{
+//!     CommandOptionType, CreateCommand, CreateCommandOption, GuildId,
+//! };
+//! use bevy_discord::slash_commands::{CommandInvoked, DiscordCommandAppExt};
+//!
+//! fn ping(In(invoked): In<CommandInvoked>) {
+//!     info!("{} used /ping", invoked.user.name);
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DiscordBotPlugin::new(DiscordBotConfig::default()))
+//!     .add_discord_command(CreateCommand::new("ping").description("A simple ping command"))
+//!     .add_discord_guild_command(
+//!         GuildId::new(1234),
+//!         CreateCommand::new("admin")
+//!             .description("Game server administration")
+//!             .add_option(CreateCommandOption::new(
+//!                 CommandOptionType::SubCommand,
+//!                 "ban",
+//!                 "Bans a player from the game server",
+//!             )),
+//!     )
+//!     .add_discord_command_system("ping", ping)
+//!     .add_discord_command_observer("admin ban", |invoked: On<CommandInvoked>| {
+//!         info!("{} used /admin ban", invoked.user.name);
+//!     })
+//!     .run();
+//! ```
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::IntoObserverSystem;
+use serenity::all::{CreateCommand, GuildId};
+
+use crate::checks::CommandChecks;
+
+mod autocomplete;
+mod parse;
+mod registry;
+mod routing;
+
+pub use autocomplete::{AutocompleteQuery, AutocompleteSuggestion};
+pub use bevy_discord_macros::DiscordCommand;
+#[doc(hidden)]
+pub use parse::__private;
+pub use parse::{CommandParseError, DiscordCommand, DiscordOptionValue, ParsedCommand};
+pub use registry::CommandSyncReport;
+pub use routing::CommandInvoked;
+
+/// Extension trait for [`App`] to register and handle application commands, see the
+/// [module](self) for more information.
+pub trait DiscordCommandAppExt {
+    /// Registers a global command, available in every guild and in DMs.
+    ///
+    /// Once a global command has been registered, the global commands of the bot that weren't
+    /// registered are deleted.
+    fn add_discord_command(&mut self, command: CreateCommand) -> &mut Self;
+
+    /// Registers a command that is only available in the guild.
+    ///
+    /// Once a command has been registered in a guild, the commands of the bot in that guild that
+    /// weren't registered are deleted.
+    fn add_discord_guild_command(&mut self, guild_id: GuildId, command: CreateCommand)
+    -> &mut Self;
+
+    /// Runs the system whenever the command is used.
+    ///
+    /// The path is the name of the command, followed by the subcommand group and subcommand
+    /// if it has any, e.g. `admin ban`.
+    fn add_discord_command_system<M>(
+        &mut self,
+        path: &str,
+        system: impl IntoSystem<In<CommandInvoked>, (), M> + 'static,
+    ) -> &mut Self;
+
+    /// Runs the observer whenever the command is used, see
+    /// [`add_discord_command_system`](Self::add_discord_command_system) for the path.
+    fn add_discord_command_observer<B: Bundle, M>(
+        &mut self,
+        path: &str,
+        observer: impl IntoObserverSystem<CommandInvoked, B, M>,
+    ) -> &mut Self;
+
+    /// Evaluates the checks before the command, or one of its subcommands, is dispatched, see
+    /// [`checks`](crate::checks).
+    fn add_discord_command_checks(&mut self, path: &str, checks: CommandChecks) -> &mut Self;
+
+    /// Answers the autocomplete interactions of the option with the suggestions returned by the
+    /// provider, see [`add_discord_command_system`](Self::add_discord_command_system) for the
+    /// path.
+    ///
+    /// The provider is a system, so it can read the world, e.g. to suggest the names of the
+    /// players that are online. Only the suggestions whose name contains what the user has typed
+    /// so far are sent, the ones starting with it first. The option must have autocomplete
+    /// enabled.
+    fn add_discord_autocomplete<M>(
+        &mut self,
+        path: &str,
+        option: &str,
+        provider: impl IntoSystem<In<AutocompleteQuery>, Vec<AutocompleteSuggestion>, M> + 'static,
+    ) -> &mut Self;
+
+    /// Registers the global command described by `T`, and dispatches a [`ParsedCommand`]
+    /// whenever it is used.
+    fn add_typed_discord_command<T: DiscordCommand>(&mut self) -> &mut Self;
+
+    /// Registers the command described by `T` in the guild, and dispatches a [`ParsedCommand`]
+    /// whenever it is used.
+    fn add_typed_discord_guild_command<T: DiscordCommand>(
+        &mut self,
+        guild_id: GuildId,
+    ) -> &mut Self;
+}
+
+impl DiscordCommandAppExt for App {
+    fn add_discord_command(&mut self, command: CreateCommand) -> &mut Self {
+        registry::register_command(self, None, command);
+        self
+    }
+
+    fn add_discord_guild_command(
+        &mut self,
+        guild_id: GuildId,
+        command: CreateCommand,
+    ) -> &mut Self {
+        registry::register_command(self, Some(guild_id), command);
+        self
+    }
+
+    fn add_discord_command_system<M>(
+        &mut self,
+        path: &str,
+        system: impl IntoSystem<In<CommandInvoked>, (), M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        routing::add_route_system(self, path, system);
+        self
+    }
+
+    fn add_discord_command_observer<B: Bundle, M>(
+        &mut self,
+        path: &str,
+        observer: impl IntoObserverSystem<CommandInvoked, B, M>,
+    ) -> &mut Self {
+        let entity = routing::route_entity(self, path);
+        self.world_mut().entity_mut(entity).observe(observer);
+        self
+    }
+
+    fn add_discord_command_checks(&mut self, path: &str, checks: CommandChecks) -> &mut Self {
+        routing::add_route_checks(self, path, checks);
+        self
+    }
+
+    fn add_discord_autocomplete<M>(
+        &mut self,
+        path: &str,
+        option: &str,
+        provider: impl IntoSystem<In<AutocompleteQuery>, Vec<AutocompleteSuggestion>, M> + 'static,
+    ) -> &mut Self {
+        let provider = self.world_mut().register_system(provider);
+        autocomplete::add_provider(self, path, option, provider);
+        self
+    }
+
+    fn add_typed_discord_command<T: DiscordCommand>(&mut self) -> &mut Self {
+        self.add_discord_command(T::create_command())
+            .add_message::<ParsedCommand<T>>()
+            .add_discord_command_system(T::NAME, parse::parse_command::<T>)
+    }
+
+    fn add_typed_discord_guild_command<T: DiscordCommand>(
+        &mut self,
+        guild_id: GuildId,
+    ) -> &mut Self {
+        self.add_discord_guild_command(guild_id, T::create_command())
+            .add_message::<ParsedCommand<T>>()
+            .add_discord_command_system(T::NAME, parse::parse_command::<T>)
+    }
+}
@@ -0,0 +1,203 @@
+use bevy_ecs::prelude::*;
+use serenity::all::{
+    AttachmentId, ChannelId, CommandDataOption, CommandDataOptionValue, CommandOptionType,
+    GenericId, RoleId, UserId,
+};
+use std::fmt;
+use tracing::warn;
+
+use super::CommandInvoked;
+
+/// A slash command whose definition and options are described by a struct, derive it with
+/// [`DiscordCommand`](derive@super::DiscordCommand).
+///
+/// Register it with
+/// [`DiscordCommandAppExt::add_typed_discord_command`](super::DiscordCommandAppExt::add_typed_discord_command)
+/// and read it as [`ParsedCommand`].
+///
+/// The command and every option need a `description`. The options can also have a `name`, and
+/// `min` and `max` for `i64` and `f64` fields, `min_length` and `max_length`, `choice`s and
+/// `autocomplete`. The required options are listed before the optional ones, as discord
+/// requires, the fields keep their order otherwise.
+pub trait DiscordCommand: Sized + Send + Sync + 'static {
+    /// Name of the command.
+    const NAME: &'static str;
+
+    /// Returns the definition of the command that is synced with Discord.
+    fn create_command() -> serenity::all::CreateCommand;
+
+    /// Builds the command from the options it was used with.
+    fn from_options(options: &[CommandDataOption]) -> Result<Self, CommandParseError>;
+}
+
+/// Types that can be used as the fields of a [`DiscordCommand`], wrap them in an `Option` to
+/// make the option optional.
+pub trait DiscordOptionValue: Sized {
+    /// Type of the option.
+    const KIND: CommandOptionType;
+
+    /// Returns `None` if the value has another type.
+    fn from_value(value: &CommandDataOptionValue) -> Option<Self>;
+}
+
+macro_rules! impl_discord_option_value {
+    ($type:ty, $kind:ident, $variant:ident) => {
+        impl DiscordOptionValue for $type {
+            const KIND: CommandOptionType = CommandOptionType::$kind;
+
+            fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+                match value {
+                    CommandDataOptionValue::$variant(value) => Some(value.clone()),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_discord_option_value!(String, String, String);
+impl_discord_option_value!(i64, Integer, Integer);
+impl_discord_option_value!(f64, Number, Number);
+impl_discord_option_value!(bool, Boolean, Boolean);
+impl_discord_option_value!(UserId, User, User);
+impl_discord_option_value!(ChannelId, Channel, Channel);
+impl_discord_option_value!(RoleId, Role, Role);
+impl_discord_option_value!(GenericId, Mentionable, Mentionable);
+impl_discord_option_value!(AttachmentId, Attachment, Attachment);
+
+/// Errors returned by [`DiscordCommand::from_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandParseError {
+    /// A required option wasn't given.
+    MissingOption(String),
+    /// The option has another type than the field.
+    InvalidOption(String),
+}
+
+impl fmt::Display for CommandParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingOption(name) => write!(f, "the option `{name}` is missing"),
+            Self::InvalidOption(name) => write!(f, "the option `{name}` has an invalid type"),
+        }
+    }
+}
+
+impl std::error::Error for CommandParseError {}
+
+/// Dispatched whenever a command registered with
+/// [`DiscordCommandAppExt::add_typed_discord_command`](super::DiscordCommandAppExt::add_typed_discord_command)
+/// is used.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_discord::serenity::all::UserId;
+/// use bevy_discord::slash_commands::{DiscordCommand, ParsedCommand};
+///
+/// #[derive(DiscordCommand)]
+/// #[command(description = "Bans a player from the game server")]
+/// struct Ban {
+///     #[option(description = "Player to ban")]
+///     user: UserId,
+///     #[option(description = "Reason", choice("Cheating", "cheating"), choice("Spam", "spam"))]
+///     reason: String,
+///     #[option(description = "Number of days", min = 1, max = 30)]
+///     days: Option<i64>,
+/// }
+///
+/// fn ban(mut bans: MessageReader<ParsedCommand<Ban>>) {
+///     for ban in bans.read() {
+///         info!("Banning {} for {:?} days", ban.command.user, ban.command.days);
+///     }
+/// }
+/// ```
+#[derive(Message, Debug, Clone)]
+pub struct ParsedCommand<T: DiscordCommand> {
+    pub command: T,
+    pub invoked: CommandInvoked,
+}
+
+/// Writes [`ParsedCommand`], added as the handler of the command.
+pub(crate) fn parse_command<T: DiscordCommand>(
+    In(invoked): In<CommandInvoked>,
+    mut parsed: MessageWriter<ParsedCommand<T>>,
+) {
+    match T::from_options(&invoked.options) {
+        Ok(command) => {
+            parsed.write(ParsedCommand { command, invoked });
+        }
+        Err(err) => warn!("Unable to parse the command `{}`: {}", invoked.path, err),
+    }
+}
+
+/// Used by the code generated by [`DiscordCommand`](derive@super::DiscordCommand).
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+    use serenity::all::CreateCommandOption;
+
+    pub fn required<T: DiscordOptionValue>(
+        options: &[CommandDataOption],
+        name: &str,
+    ) -> Result<T, CommandParseError> {
+        optional(options, name)?.ok_or_else(|| CommandParseError::MissingOption(name.to_string()))
+    }
+
+    pub fn optional<T: DiscordOptionValue>(
+        options: &[CommandDataOption],
+        name: &str,
+    ) -> Result<Option<T>, CommandParseError> {
+        let Some(option) = options.iter().find(|option| option.name == name) else {
+            return Ok(None);
+        };
+
+        T::from_value(&option.value)
+            .map(Some)
+            .ok_or_else(|| CommandParseError::InvalidOption(name.to_string()))
+    }
+
+    /// Options that can have a minimum and maximum value.
+    #[diagnostic::on_unimplemented(
+        message = "`min` and `max` can only be set on `i64` and `f64` options, not on `{Self}`"
+    )]
+    pub trait NumericOption: DiscordOptionValue {
+        type Bound;
+
+        fn min_value(option: CreateCommandOption, value: Self::Bound) -> CreateCommandOption;
+
+        fn max_value(option: CreateCommandOption, value: Self::Bound) -> CreateCommandOption;
+    }
+
+    // Serenity only takes unsigned integer bounds, negative ones are sent as numbers
+    impl NumericOption for i64 {
+        type Bound = i64;
+
+        fn min_value(option: CreateCommandOption, value: i64) -> CreateCommandOption {
+            match u64::try_from(value) {
+                Ok(value) => option.min_int_value(value),
+                Err(_) => option.min_number_value(value as f64),
+            }
+        }
+
+        fn max_value(option: CreateCommandOption, value: i64) -> CreateCommandOption {
+            match u64::try_from(value) {
+                Ok(value) => option.max_int_value(value),
+                Err(_) => option.max_number_value(value as f64),
+            }
+        }
+    }
+
+    impl NumericOption for f64 {
+        type Bound = f64;
+
+        fn min_value(option: CreateCommandOption, value: f64) -> CreateCommandOption {
+            option.min_number_value(value)
+        }
+
+        fn max_value(option: CreateCommandOption, value: f64) -> CreateCommandOption {
+            option.max_number_value(value)
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use serde_json::Value;
+use serenity::all::{Command, CreateCommand, GuildId, Http};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::DiscordSystems;
+use crate::channel::ChannelRes;
+use crate::error::DiscordHttpError;
+use crate::messages::bot::{BotReadyMessage, CommandSyncMessage};
+use crate::messages::{MessageCollectionBot, send_events_bot};
+use crate::runtime::PendingRequests;
+
+/// Adds the command to [`DiscordCommandRegistry`], `None` registers a global command.
+pub(crate) fn register_command(app: &mut App, guild_id: Option<GuildId>, command: CreateCommand) {
+    let mut registry = app
+        .world_mut()
+        .get_resource_or_init::<DiscordCommandRegistry>();
+
+    match guild_id {
+        Some(guild_id) => registry.guilds.entry(guild_id).or_default().push(command),
+        None => registry.global.push(command),
+    }
+
+    if !app.is_plugin_added::<CommandRegistryPlugin>() {
+        app.add_plugins(CommandRegistryPlugin);
+    }
+}
+
+/// Commands registered with [`DiscordCommandAppExt`](super::DiscordCommandAppExt).
+#[derive(Resource, Default)]
+pub(crate) struct DiscordCommandRegistry {
+    pub(crate) global: Vec<CreateCommand>,
+    pub(crate) guilds: HashMap<GuildId, Vec<CreateCommand>>,
+}
+
+/// Syncs [`DiscordCommandRegistry`] once the bot is ready, added by
+/// [`DiscordCommandAppExt`](super::DiscordCommandAppExt).
+struct CommandRegistryPlugin;
+
+impl Plugin for CommandRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            sync_registered_commands
+                .after(send_events_bot)
+                .in_set(DiscordSystems),
+        );
+    }
+}
+
+/// Names of the commands changed by a sync, see
+/// [`CommandSyncMessage`](crate::messages::bot::CommandSyncMessage).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSyncReport {
+    /// Commands that didn't exist yet.
+    pub created: Vec<String>,
+    /// Commands that existed, but were different.
+    pub updated: Vec<String>,
+    /// Commands that existed, but weren't registered.
+    pub deleted: Vec<String>,
+    /// Commands that were already up to date.
+    pub unchanged: Vec<String>,
+}
+
+fn sync_registered_commands(
+    mut ready: MessageReader<BotReadyMessage>,
+    registry: Res<DiscordCommandRegistry>,
+    channel_res: Res<ChannelRes<MessageCollectionBot>>,
+    pending: Res<PendingRequests>,
+) {
+    for event in ready.read() {
+        // Every shard is ready on its own, but the commands only have to be synced once
+        if event
+            .data_about_bot
+            .shard
+            .is_some_and(|shard| shard.id.0 != 0)
+        {
+            continue;
+        }
+
+        let scopes = (!registry.global.is_empty())
+            .then(|| (None, registry.global.clone()))
+            .into_iter()
+            .chain(
+                registry
+                    .guilds
+                    .iter()
+                    .map(|(guild_id, commands)| (Some(*guild_id), commands.clone())),
+            );
+
+        for (guild_id, commands) in scopes {
+            let http = event.ctx.http.clone();
+            let tx = channel_res.tx.clone();
+
+            pending.spawn(async move {
+                let result = sync_commands(&http, guild_id, commands)
+                    .await
+                    .map_err(DiscordHttpError::from);
+
+                if let Err(err) = &result {
+                    error!("Unable to sync the discord commands: {}", err);
+                }
+
+                if tx
+                    .send_async(MessageCollectionBot::CommandSyncMessage(
+                        CommandSyncMessage { guild_id, result },
+                    ))
+                    .await
+                    .is_err()
+                {
+                    error!("Unable to send event to the channel")
+                }
+            });
+        }
+    }
+}
+
+/// Creates, updates and deletes the commands of the scope so that they match `commands`.
+async fn sync_commands(
+    http: &Arc<Http>,
+    guild_id: Option<GuildId>,
+    commands: Vec<CreateCommand>,
+) -> serenity::Result<CommandSyncReport> {
+    let existing = match guild_id {
+        Some(guild_id) => guild_id.get_commands(http).await?,
+        None => Command::get_global_commands(http).await?,
+    };
+
+    let mut existing: HashMap<(String, u64), (Command, Value)> = existing
+        .into_iter()
+        .map(|command| {
+            let value = serde_json::to_value(&command)?;
+            Ok((command_key(&value), (command, value)))
+        })
+        .collect::<serde_json::Result<_>>()?;
+
+    let mut report = CommandSyncReport::default();
+
+    for command in commands {
+        let mut value = serde_json::to_value(&command)?;
+        normalize(&mut value);
+        let key = command_key(&value);
+
+        match existing.remove(&key) {
+            Some((_, existing)) if matches(&value, &existing) => report.unchanged.push(key.0),
+            Some((existing, _)) => {
+                match guild_id {
+                    Some(guild_id) => {
+                        guild_id.edit_command(http, existing.id, command).await?;
+                    }
+                    None => {
+                        Command::edit_global_command(http, existing.id, command).await?;
+                    }
+                }
+                report.updated.push(key.0);
+            }
+            None => {
+                match guild_id {
+                    Some(guild_id) => {
+                        guild_id.create_command(http, command).await?;
+                    }
+                    None => {
+                        Command::create_global_command(http, command).await?;
+                    }
+                }
+                report.created.push(key.0);
+            }
+        }
+    }
+
+    for ((name, _), (command, _)) in existing {
+        match guild_id {
+            Some(guild_id) => guild_id.delete_command(http, command.id).await?,
+            None => Command::delete_global_command(http, command.id).await?,
+        }
+        report.deleted.push(name);
+    }
+
+    Ok(report)
+}
+
+/// Sets the fields that serenity leaves unset on the desired commands to the value discord
+/// returns for them.
+fn normalize(command: &mut Value) {
+    if let Value::Object(command) = command {
+        let kind = command.entry("type").or_insert(Value::Null);
+        if kind.is_null() {
+            // Slash commands are the default type
+            *kind = Value::from(1);
+        }
+    }
+}
+
+/// Commands are identified by their name and type, e.g. a slash command and a context menu
+/// command can share a name.
+fn command_key(command: &Value) -> (String, u64) {
+    let name = command["name"].as_str().unwrap_or_default().to_string();
+    let kind = command["type"].as_u64().unwrap_or(1);
+
+    (name, kind)
+}
+
+/// Fields that discord sets on the commands itself.
+const SERVER_FIELDS: &[&str] = &[
+    "id",
+    "application_id",
+    "guild_id",
+    "version",
+    "name_localized",
+    "description_localized",
+];
+
+/// Fields that discord fills with its own default when they aren't set, so they are only
+/// compared when they are set on the desired command.
+const DEFAULTED_FIELDS: &[&str] = &["dm_permission", "integration_types", "contexts", "handler"];
+
+/// Returns `true` if the fields of the `desired` command, and of the `existing` one, have the same
+/// value on both. Fields that aren't set are the same as empty ones, e.g. the empty description
+/// of the context menu commands.
+fn matches(desired: &Value, existing: &Value) -> bool {
+    match (desired, existing) {
+        (Value::Object(desired), Value::Object(existing)) => desired
+            .keys()
+            .chain(existing.keys())
+            .filter(|key| !SERVER_FIELDS.contains(&key.as_str()))
+            .all(|key| match (desired.get(key), existing.get(key)) {
+                (None, Some(_)) if DEFAULTED_FIELDS.contains(&key.as_str()) => true,
+                (desired, existing) => matches(
+                    desired.unwrap_or(&Value::Null),
+                    existing.unwrap_or(&Value::Null),
+                ),
+            }),
+        (Value::Array(desired), Value::Array(existing)) => {
+            desired.len() == existing.len()
+                && desired
+                    .iter()
+                    .zip(existing)
+                    .all(|(desired, existing)| matches(desired, existing))
+        }
+        (desired, existing) => desired == existing || (is_empty(desired) && is_empty(existing)),
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => true,
+        Value::String(string) => string.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use serenity::all::{CommandOptionType, CommandType, CreateCommandOption};
+
+    /// Command as returned by discord, with the `changes` applied, as it is compared by a sync.
+    fn fetched(changes: Value) -> Value {
+        let mut command = json!({
+            "id": "1230000000000000001",
+            "application_id": "1230000000000000000",
+            "version": "1230000000000000002",
+            "default_member_permissions": null,
+            "type": 1,
+            "name": "ban",
+            "description": "Bans a member",
+            "dm_permission": true,
+            "contexts": null,
+            "integration_types": [0],
+            "nsfw": false,
+            "options": [{
+                "type": 4,
+                "name": "days",
+                "description": "Days of messages",
+                "min_value": 0
+            }]
+        });
+        command
+            .as_object_mut()
+            .unwrap()
+            .extend(changes.as_object().unwrap().clone());
+
+        let command: Command = serde_json::from_value(command).unwrap();
+        serde_json::to_value(command).unwrap()
+    }
+
+    fn desired(command: CreateCommand) -> Value {
+        let mut command = serde_json::to_value(command).unwrap();
+        normalize(&mut command);
+        command
+    }
+
+    fn command() -> CreateCommand {
+        CreateCommand::new("ban")
+            .description("Bans a member")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "days", "Days of messages")
+                    .min_int_value(0),
+            )
+    }
+
+    #[test]
+    fn fetched_commands_match() {
+        assert!(matches(&desired(command()), &fetched(json!({}))));
+        assert_eq!(command_key(&desired(command())), ("ban".to_string(), 1));
+    }
+
+    #[test]
+    fn context_menu_commands_match() {
+        let fetched = fetched(json!({
+            "type": 2,
+            "name": "Profile",
+            "description": "",
+            "options": []
+        }));
+        let desired = desired(CreateCommand::new("Profile").kind(CommandType::User));
+
+        assert!(matches(&desired, &fetched));
+        assert_eq!(command_key(&desired), command_key(&fetched));
+    }
+
+    #[test]
+    fn removed_fields_dont_match() {
+        let desired = desired(command());
+
+        assert!(!matches(&desired, &fetched(json!({ "nsfw": true }))));
+        assert!(!matches(
+            &desired,
+            &fetched(json!({ "default_member_permissions": "4" }))
+        ));
+        assert!(!matches(
+            &desired,
+            &fetched(json!({ "name_localizations": { "fr": "bannir" } }))
+        ));
+        assert!(!matches(&desired, &fetched(json!({ "description": "" }))));
+    }
+
+    #[test]
+    fn removed_option_bounds_dont_match() {
+        let desired = desired(
+            CreateCommand::new("ban")
+                .description("Bans a member")
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "days",
+                    "Days of messages",
+                )),
+        );
+
+        assert!(!matches(&desired, &fetched(json!({}))));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn syncing_unchanged_commands_changes_nothing() {
+        use crate::runtime::tokio_runtime;
+        use crate::testing::MockDiscordServer;
+        use serenity::all::{ApplicationId, HttpBuilder};
+
+        let server = MockDiscordServer::start().unwrap();
+        let http = Arc::new(
+            HttpBuilder::new("token")
+                .proxy(server.url())
+                .ratelimiter_disabled(true)
+                .application_id(ApplicationId::new(1))
+                .build(),
+        );
+        let commands = || {
+            vec![
+                command(),
+                CreateCommand::new("Profile").kind(CommandType::User),
+            ]
+        };
+
+        let report = tokio_runtime()
+            .block_on(sync_commands(&http, None, commands()))
+            .unwrap();
+        assert_eq!(report.created, ["ban", "Profile"]);
+
+        server.take_requests();
+        let report = tokio_runtime()
+            .block_on(sync_commands(&http, None, commands()))
+            .unwrap();
+        assert_eq!(
+            report,
+            CommandSyncReport {
+                unchanged: vec!["ban".to_string(), "Profile".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+}
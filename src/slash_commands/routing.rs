@@ -0,0 +1,264 @@
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemId;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandInteraction, CreateInteractionResponse,
+    CreateInteractionResponseMessage, Interaction, User,
+};
+use std::collections::HashMap;
+
+use crate::DiscordSystems;
+use crate::checks::{
+    CheckContext, CommandChecks, CommandChecksPlugin, CommandCooldowns, CommandOwners,
+    CommandRejected, RejectionReason,
+};
+use crate::messages::bot::{InteractionCreateMessage, UnhandledCommandMessage};
+use crate::messages::http::DiscordRequest;
+use crate::messages::{read_with_context, send_events_bot};
+use crate::modals::{DiscordModal, open_modal};
+
+/// Triggered on the route of a command whenever it is used, see
+/// [`DiscordCommandAppExt::add_discord_command_system`](super::DiscordCommandAppExt::add_discord_command_system)
+/// and [`DiscordCommandAppExt::add_discord_command_observer`](super::DiscordCommandAppExt::add_discord_command_observer).
+#[derive(EntityEvent, Debug, Clone)]
+pub struct CommandInvoked {
+    /// Entity of the route the command was sent to.
+    pub entity: Entity,
+    /// Name of the command followed by its subcommand group and subcommand, e.g. `admin ban`.
+    pub path: String,
+    pub interaction: CommandInteraction,
+    /// User that used the command.
+    pub user: User,
+    /// Options of the subcommand that was used, or of the command if it has no subcommands.
+    pub options: Vec<CommandDataOption>,
+}
+
+impl CommandInvoked {
+    /// Returns the value of the option.
+    pub fn option(&self, name: &str) -> Option<&CommandDataOptionValue> {
+        self.options
+            .iter()
+            .find(|option| option.name == name)
+            .map(|option| &option.value)
+    }
+
+    /// Returns the request responding to the interaction, write it as a message.
+    pub fn respond(&self, response: CreateInteractionResponse) -> DiscordRequest {
+        DiscordRequest::RespondInteraction {
+            interaction_id: self.interaction.id,
+            token: self.interaction.token.clone(),
+            response,
+        }
+    }
+
+    /// Returns the request opening the modal in response to the interaction.
+    pub fn open_modal<T: DiscordModal>(&self) -> DiscordRequest {
+        open_modal::<T>(self.interaction.id, &self.interaction.token)
+    }
+}
+
+/// Handlers of a command path, the observers are attached to the same entity.
+#[derive(Component, Default)]
+pub(crate) struct CommandRoute {
+    systems: Vec<SystemId<In<CommandInvoked>>>,
+}
+
+/// Entities of the [`CommandRoute`]s by their path.
+#[derive(Resource, Default)]
+pub(crate) struct CommandRoutes(HashMap<String, Entity>);
+
+/// Routes the used commands to their handlers, added by
+/// [`DiscordCommandAppExt`](super::DiscordCommandAppExt).
+pub(crate) struct CommandRoutingPlugin;
+
+impl Plugin for CommandRoutingPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CommandChecksPlugin>() {
+            app.add_plugins(CommandChecksPlugin);
+        }
+
+        read_with_context::<InteractionCreateMessage>(app);
+
+        app.init_resource::<CommandRoutes>()
+            .add_message::<UnhandledCommandMessage>()
+            .add_systems(
+                Update,
+                route_commands.after(send_events_bot).in_set(DiscordSystems),
+            );
+    }
+}
+
+/// Returns the entity of the route of the path, spawning it if there is none.
+pub(crate) fn route_entity(app: &mut App, path: &str) -> Entity {
+    if !app.is_plugin_added::<CommandRoutingPlugin>() {
+        app.add_plugins(CommandRoutingPlugin);
+    }
+
+    let path = normalize_path(path);
+    let world = app.world_mut();
+
+    if let Some(entity) = world.resource::<CommandRoutes>().0.get(&path) {
+        return *entity;
+    }
+
+    let entity = world.spawn(CommandRoute::default()).id();
+    world.resource_mut::<CommandRoutes>().0.insert(path, entity);
+    entity
+}
+
+pub(crate) fn add_route_system(app: &mut App, path: &str, system: SystemId<In<CommandInvoked>>) {
+    let entity = route_entity(app, path);
+
+    if let Some(mut route) = app.world_mut().get_mut::<CommandRoute>(entity) {
+        route.systems.push(system);
+    }
+}
+
+pub(crate) fn add_route_checks(app: &mut App, path: &str, checks: CommandChecks) {
+    let entity = route_entity(app, path);
+    app.world_mut().entity_mut(entity).insert(checks);
+}
+
+/// Collapses the whitespace, so that `admin  ban` and `admin ban` are the same path.
+pub(crate) fn normalize_path(path: &str) -> String {
+    path.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the path of the command and the options of the subcommand that was used.
+pub(crate) fn resolve(interaction: &CommandInteraction) -> (String, Vec<CommandDataOption>) {
+    let mut path = interaction.data.name.clone();
+    let mut options = interaction.data.options.clone();
+
+    loop {
+        match options.first() {
+            Some(CommandDataOption {
+                name,
+                value:
+                    CommandDataOptionValue::SubCommandGroup(nested)
+                    | CommandDataOptionValue::SubCommand(nested),
+                ..
+            }) => {
+                path.push(' ');
+                path.push_str(name);
+                options = nested.clone();
+            }
+            _ => return (path, options),
+        }
+    }
+}
+
+/// Evaluates the checks of the path and of its parent commands, and starts their cooldowns if
+/// they all pass.
+fn check_route(
+    path: &str,
+    interaction: &CommandInteraction,
+    routes: &CommandRoutes,
+    checks: &Query<&CommandChecks>,
+    owners: &CommandOwners,
+    cooldowns: &mut CommandCooldowns,
+) -> Result<(), (RejectionReason, CheckContext, bool)> {
+    let member = interaction.member.as_deref();
+    let context = CheckContext {
+        user_id: interaction.user.id,
+        channel_id: interaction.channel_id,
+        guild_id: interaction.guild_id,
+        permissions: member.and_then(|member| member.permissions),
+        roles: member
+            .map(|member| member.roles.clone())
+            .unwrap_or_default(),
+    };
+
+    let mut started = Vec::new();
+    let mut parent = String::new();
+    for name in path.split(' ') {
+        if !parent.is_empty() {
+            parent.push(' ');
+        }
+        parent.push_str(name);
+
+        let Some(checks) = routes
+            .0
+            .get(&parent)
+            .and_then(|entity| checks.get(*entity).ok())
+        else {
+            continue;
+        };
+
+        match checks.evaluate(&parent, &context, owners, cooldowns) {
+            Ok(cooldowns) => started.extend(cooldowns),
+            Err(reason) => return Err((reason, context, checks.responds())),
+        }
+    }
+
+    cooldowns.start(started);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn route_commands(
+    mut interactions: MessageReader<InteractionCreateMessage>,
+    mut unhandled: MessageWriter<UnhandledCommandMessage>,
+    mut rejected: MessageWriter<CommandRejected>,
+    mut requests: MessageWriter<DiscordRequest>,
+    routes: Res<CommandRoutes>,
+    command_routes: Query<&CommandRoute>,
+    checks: Query<&CommandChecks>,
+    owners: Res<CommandOwners>,
+    mut cooldowns: ResMut<CommandCooldowns>,
+    mut commands: Commands,
+) {
+    for event in interactions.read() {
+        let Interaction::Command(interaction) = &event.interaction else {
+            continue;
+        };
+
+        let (path, options) = resolve(interaction);
+
+        let Some(entity) = routes.0.get(&path).copied() else {
+            unhandled.write(UnhandledCommandMessage {
+                path,
+                interaction: interaction.clone(),
+            });
+            continue;
+        };
+
+        if let Err((reason, context, respond)) = check_route(
+            &path,
+            interaction,
+            &routes,
+            &checks,
+            &owners,
+            &mut cooldowns,
+        ) {
+            if respond {
+                requests.write(DiscordRequest::RespondInteraction {
+                    interaction_id: interaction.id,
+                    token: interaction.token.clone(),
+                    response: CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(reason.to_string())
+                            .ephemeral(true),
+                    ),
+                });
+            }
+            rejected.write(CommandRejected::new(&path, &context, reason));
+            continue;
+        }
+
+        let invoked = CommandInvoked {
+            entity,
+            path,
+            interaction: interaction.clone(),
+            user: interaction.user.clone(),
+            options,
+        };
+
+        if let Ok(route) = command_routes.get(entity) {
+            for system in &route.systems {
+                commands.run_system_with(*system, invoked.clone());
+            }
+        }
+
+        commands.trigger(invoked);
+    }
+}
@@ -0,0 +1,61 @@
+//! Bevy [`States`] that follow the connection to Discord.
+//!
+//! - [`DiscordConnectionState`]: Connection of the Discord bot (available with `bot` feature)
+//! - [`RichPresenceConnectionState`]: Connection to the local Discord client (available with `rich_presence` feature)
+//!
+//! These states are initialized by their plugins, which also add bevy's `StatesPlugin` if it
+//! hasn't been added yet. Use them with `OnEnter`/`OnExit` schedules or the `in_state` run
+//! condition to run systems only while Discord is available.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::state::DiscordConnectionState;
+//!
+//! fn announce_server(/* ... */) {}
+//!
+//! App::new()
+//!     .add_systems(OnEnter(DiscordConnectionState::Ready), announce_server);
+//! ```
+
+use bevy_state::state::States;
+
+/// Connection state of the Discord bot.
+///
+/// With multiple shards, the state follows the most recent update of any shard.
+#[cfg(feature = "bot")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bot")))]
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DiscordConnectionState {
+    /// The client isn't running, either it hasn't been started yet or it has been shut down.
+    #[default]
+    Disconnected,
+    /// The client is connecting to the gateway.
+    Connecting,
+    /// The bot is connected and has received [`BotReadyMessage`](crate::messages::bot::BotReadyMessage).
+    Ready,
+    /// The connection was interrupted and is being resumed.
+    Resuming,
+    /// The client stopped because of an error, see
+    /// [`DiscordBotErrorMessage`](crate::messages::bot::DiscordBotErrorMessage).
+    Failed,
+}
+
+/// Connection state of Discord Rich Presence.
+#[cfg(feature = "rich_presence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rich_presence")))]
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RichPresenceConnectionState {
+    /// Not connected to the local Discord client.
+    #[default]
+    Disconnected,
+    /// The Rich Presence client has been created and is connecting to Discord.
+    Connecting,
+    /// Connected, [`RpReadyMessage`](crate::messages::rich_presence::RpReadyMessage) has been
+    /// received.
+    Ready,
+    /// The Rich Presence client couldn't be created, see
+    /// [`RichPresenceErrorMessage`](crate::messages::rich_presence::RichPresenceErrorMessage).
+    Failed,
+}
@@ -0,0 +1,276 @@
+//! Running async Discord code from bevy systems.
+//!
+//! [`DiscordCommandsExt::spawn_discord_task`] runs a future on the
+//! [tokio runtime](crate::runtime::tokio_runtime) and stores its output in a [`DiscordTask`]
+//! component, which works like bevy's `Task` but doesn't need an async executor from bevy.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::serenity::all::ChannelId;
+//! use bevy_discord::task::{DiscordCommandsExt, DiscordTask, DiscordTaskFinished};
+//!
+//! fn fetch_channel_name(mut commands: Commands) {
+//!     commands
+//!         .spawn_discord_task(|http| async move {
+//!             ChannelId::new(1234)
+//!                 .name(&http)
+//!                 .await
+//!                 .unwrap_or_default()
+//!         })
+//!         .observe(
+//!             |finished: On<DiscordTaskFinished>, mut tasks: Query<&mut DiscordTask<String>>| {
+//!                 if let Ok(mut task) = tasks.get_mut(finished.entity) {
+//!                     info!("Channel name: {:?}", task.poll());
+//!                 }
+//!             },
+//!         );
+//! }
+//! ```
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::EntityCommands;
+use flume::{Receiver, Sender};
+use serenity::all::Http;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::AbortHandle;
+use tracing::error;
+
+use crate::res::DiscordHttpResource;
+use crate::runtime::tokio_runtime;
+
+/// Holds the output of a future spawned with [`DiscordCommandsExt::spawn_discord_task`].
+///
+/// Either poll it every frame, or observe [`DiscordTaskFinished`] on its entity. The future is
+/// cancelled when the component is dropped, e.g. when its entity is despawned.
+#[derive(Component)]
+pub struct DiscordTask<T: Send + 'static> {
+    output: Receiver<T>,
+    panicked: Arc<AtomicBool>,
+    abort: AbortHandle,
+}
+
+impl<T: Send + 'static> DiscordTask<T> {
+    /// Returns `true` once the output can be taken with [`DiscordTask::poll`].
+    pub fn is_finished(&self) -> bool {
+        !self.output.is_empty()
+    }
+
+    /// Returns `true` if the future panicked, in which case there is no output.
+    pub fn is_panicked(&self) -> bool {
+        self.panicked.load(Ordering::SeqCst)
+    }
+
+    /// Takes the output of the future, `None` if it hasn't finished yet or was already taken.
+    pub fn poll(&mut self) -> Option<T> {
+        self.output.try_recv().ok()
+    }
+}
+
+impl<T: Send + 'static> Drop for DiscordTask<T> {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Triggered on the entity of a [`DiscordTask`] once its output is available, or once its
+/// future has panicked.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct DiscordTaskFinished {
+    pub entity: Entity,
+    /// `true` if the future panicked, in which case there is no output.
+    pub panicked: bool,
+}
+
+/// Extension trait for [`Commands`] to spawn [`DiscordTask`]s.
+pub trait DiscordCommandsExt {
+    /// Spawns an entity with a [`DiscordTask`] running the future returned by `task`, which
+    /// receives the client of [`DiscordHttpResource`].
+    ///
+    /// Nothing is run if [`DiscordHttpResource`] isn't available yet, e.g. because the bot
+    /// isn't ready.
+    fn spawn_discord_task<T, F, Fut>(&mut self, task: F) -> EntityCommands<'_>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<Http>) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static;
+}
+
+impl DiscordCommandsExt for Commands<'_, '_> {
+    fn spawn_discord_task<T, F, Fut>(&mut self, task: F) -> EntityCommands<'_>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<Http>) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let entity = self.spawn_empty().id();
+
+        self.queue(move |world: &mut World| {
+            let Some(http) = world.get_resource::<DiscordHttpResource>() else {
+                error!("Unable to spawn a discord task, DiscordHttpResource isn't available");
+                return;
+            };
+
+            let future = task(http.client());
+            let pending = http.pending.clone();
+            let finished = world
+                .get_resource::<FinishedTasks>()
+                .map(|finished| finished.tx.clone());
+            let (tx, rx) = flume::bounded(1);
+            let panicked = Arc::new(AtomicBool::new(false));
+
+            // The future runs in its own task, so that a panic is reported through its handle
+            let handle = tokio_runtime().spawn(future);
+            let abort = handle.abort_handle();
+
+            let task_panicked = panicked.clone();
+            pending.spawn(async move {
+                let panicked = match handle.await {
+                    Ok(output) => {
+                        if tx.send(output).is_err() {
+                            return;
+                        }
+                        false
+                    }
+                    Err(err) if err.is_panic() => {
+                        error!("Discord task panicked");
+                        task_panicked.store(true, Ordering::SeqCst);
+                        true
+                    }
+                    // Cancelled because the component was dropped
+                    Err(_) => return,
+                };
+
+                if let Some(finished) = finished {
+                    let _ = finished.send((entity, panicked));
+                }
+            });
+
+            let task = DiscordTask {
+                output: rx,
+                panicked,
+                abort,
+            };
+
+            if let Ok(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(task);
+            }
+        });
+
+        self.entity(entity)
+    }
+}
+
+/// Carries the entities of the finished tasks back to the app that spawned them, and whether
+/// they panicked.
+#[derive(Resource)]
+pub(crate) struct FinishedTasks {
+    tx: Sender<(Entity, bool)>,
+    rx: Receiver<(Entity, bool)>,
+}
+
+impl Default for FinishedTasks {
+    fn default() -> Self {
+        let (tx, rx) = flume::unbounded();
+        Self { tx, rx }
+    }
+}
+
+/// Triggers [`DiscordTaskFinished`] for every task that has finished.
+pub(crate) fn trigger_finished_tasks(finished: Res<FinishedTasks>, mut commands: Commands) {
+    for (entity, panicked) in finished.rx.try_iter() {
+        if commands.get_entity(entity).is_ok() {
+            commands.trigger(DiscordTaskFinished { entity, panicked });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscordHttpPlugin;
+    use bevy_app::App;
+    use std::time::{Duration, Instant};
+
+    #[derive(Resource, Default)]
+    struct Finished(Vec<(Entity, bool)>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(DiscordHttpPlugin::new("token".to_string()))
+            .init_resource::<Finished>()
+            .add_observer(
+                |finished: On<DiscordTaskFinished>, mut all: ResMut<Finished>| {
+                    all.0.push((finished.entity, finished.panicked));
+                },
+            );
+        app
+    }
+
+    fn spawn<T: Send + 'static>(
+        app: &mut App,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Entity {
+        let entity = app
+            .world_mut()
+            .commands()
+            .spawn_discord_task(|_| future)
+            .id();
+        app.world_mut().flush();
+        entity
+    }
+
+    fn wait_for_finished(app: &mut App, count: usize) {
+        let started = Instant::now();
+        while app.world().resource::<Finished>().0.len() < count {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "tasks didn't finish"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+            app.update();
+        }
+    }
+
+    #[test]
+    fn finished_tasks_are_triggered_in_their_own_app() {
+        let mut first = app();
+        let mut second = app();
+        let first_task = spawn(&mut first, async { 1 });
+        let second_task = spawn(&mut second, async { 2 });
+
+        wait_for_finished(&mut first, 1);
+        wait_for_finished(&mut second, 1);
+
+        assert_eq!(
+            first.world().resource::<Finished>().0,
+            [(first_task, false)]
+        );
+        assert_eq!(
+            second.world().resource::<Finished>().0,
+            [(second_task, false)]
+        );
+
+        let mut task = first
+            .world_mut()
+            .get_mut::<DiscordTask<i32>>(first_task)
+            .unwrap();
+        assert_eq!(task.poll(), Some(1));
+    }
+
+    #[test]
+    fn panicking_tasks_are_reported() {
+        let mut app = app();
+        let entity = spawn::<()>(&mut app, async { panic!("task failed") });
+
+        wait_for_finished(&mut app, 1);
+
+        assert_eq!(app.world().resource::<Finished>().0, [(entity, true)]);
+        let task = app.world().get::<DiscordTask<()>>(entity).unwrap();
+        assert!(task.is_panicked());
+        assert!(!task.is_finished());
+    }
+}
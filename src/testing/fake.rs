@@ -0,0 +1,513 @@
+use serde_json::{Map, Value, json};
+use serenity::all::{
+    ChannelId, ChannelType, CommandInteraction, ComponentInteraction, Guild, GuildChannel, GuildId,
+    Interaction, Member, MessageId, ModalInteraction, PartialMember, Permissions, Role, RoleId,
+    User, UserId,
+};
+use serenity::model::channel::Message as SMessage;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Id of the application the fake interactions are sent to.
+pub const FAKE_APPLICATION_ID: u64 = 1;
+
+/// Id of the bot user, the author of the messages sent to [`MockDiscordServer`](super::MockDiscordServer).
+pub const FAKE_BOT_USER_ID: u64 = 2;
+
+/// Returns a new id, for the ids that aren't set.
+fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1000);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+pub(super) fn to_value(value: impl serde::Serialize) -> Value {
+    serde_json::to_value(value).expect("serenity models should serialize to JSON")
+}
+
+pub(super) fn from_value<T: serde::de::DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).expect("fake payloads should deserialize")
+}
+
+/// Builds a [`User`], named `user` by default.
+#[derive(Debug, Clone)]
+pub struct FakeUser {
+    user: User,
+}
+
+impl Default for FakeUser {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl FakeUser {
+    pub fn new(id: impl Into<UserId>) -> Self {
+        let mut user = User::default();
+        user.id = id.into();
+        user.name = "user".to_string();
+
+        Self { user }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.user.name = name.into();
+        self
+    }
+
+    pub fn bot(mut self) -> Self {
+        self.user.bot = true;
+        self
+    }
+
+    pub fn build(self) -> User {
+        self.user
+    }
+}
+
+/// Builds a [`Guild`] with its channels, roles and members.
+#[derive(Debug, Clone)]
+pub struct FakeGuild {
+    guild: Guild,
+}
+
+impl FakeGuild {
+    pub fn new(id: impl Into<GuildId>) -> Self {
+        let mut guild = Guild::default();
+        guild.id = id.into();
+        guild.name = "guild".to_string();
+
+        // The everyone role shares the id of the guild
+        let mut everyone = Role::default();
+        everyone.id = RoleId::new(guild.id.get());
+        everyone.guild_id = guild.id;
+        everyone.name = "@everyone".to_string();
+        guild.roles.insert(everyone.id, everyone);
+
+        Self { guild }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.guild.name = name.into();
+        self
+    }
+
+    pub fn owner(mut self, owner_id: impl Into<UserId>) -> Self {
+        self.guild.owner_id = owner_id.into();
+        self
+    }
+
+    pub fn text_channel(mut self, id: impl Into<ChannelId>, name: impl Into<String>) -> Self {
+        let mut channel = GuildChannel::default();
+        channel.id = id.into();
+        channel.guild_id = self.guild.id;
+        channel.kind = ChannelType::Text;
+        channel.name = name.into();
+
+        self.guild.channels.insert(channel.id, channel);
+        self
+    }
+
+    pub fn role(
+        mut self,
+        id: impl Into<RoleId>,
+        name: impl Into<String>,
+        permissions: Permissions,
+    ) -> Self {
+        let mut role = Role::default();
+        role.id = id.into();
+        role.guild_id = self.guild.id;
+        role.name = name.into();
+        role.permissions = permissions;
+
+        self.guild.roles.insert(role.id, role);
+        self
+    }
+
+    pub fn member(mut self, user: User, roles: impl IntoIterator<Item = RoleId>) -> Self {
+        let mut member = Member::default();
+        member.guild_id = self.guild.id;
+        member.roles = roles.into_iter().collect();
+        member.user = user;
+
+        self.guild.member_count += 1;
+        self.guild.members.insert(member.user.id, member);
+        self
+    }
+
+    pub fn build(self) -> Guild {
+        self.guild
+    }
+}
+
+/// Builds a [`Message`](SMessage), sent in DMs by [`FakeUser::default`] unless set otherwise.
+#[derive(Debug, Clone)]
+pub struct FakeMessage {
+    message: SMessage,
+}
+
+impl FakeMessage {
+    pub fn new(content: impl Into<String>) -> Self {
+        let mut message = SMessage::default();
+        message.id = MessageId::new(next_id());
+        message.channel_id = ChannelId::new(1);
+        message.author = FakeUser::default().build();
+        message.content = content.into();
+
+        Self { message }
+    }
+
+    pub fn id(mut self, id: impl Into<MessageId>) -> Self {
+        self.message.id = id.into();
+        self
+    }
+
+    pub fn author(mut self, author: User) -> Self {
+        self.message.author = author;
+        self
+    }
+
+    pub fn channel(mut self, channel_id: impl Into<ChannelId>) -> Self {
+        self.message.channel_id = channel_id.into();
+        self
+    }
+
+    /// Sends the message in the guild, by a member without roles unless set with
+    /// [`roles`](Self::roles).
+    pub fn guild(mut self, guild_id: impl Into<GuildId>) -> Self {
+        self.message.guild_id = Some(guild_id.into());
+        self.message.member.get_or_insert_with(|| {
+            Box::new(from_value(json!({
+                "roles": [],
+                "deaf": false,
+                "mute": false,
+            })))
+        });
+        self
+    }
+
+    /// Sets the roles of the author, which implies that the message is sent in a guild, with the
+    /// id `1` unless set with [`guild`](Self::guild).
+    pub fn roles(mut self, roles: impl IntoIterator<Item = RoleId>) -> Self {
+        let guild_id = self.message.guild_id.unwrap_or(GuildId::new(1));
+        self = self.guild(guild_id);
+
+        let member: &mut PartialMember = self
+            .message
+            .member
+            .as_mut()
+            .expect("the member is set with the guild");
+        member.roles = roles.into_iter().collect();
+        self
+    }
+
+    pub fn mention(mut self, user: User) -> Self {
+        self.message.content.push_str(&format!(" <@{}>", user.id));
+        self.message.mentions.push(user);
+        self
+    }
+
+    pub fn build(self) -> SMessage {
+        self.message
+    }
+}
+
+/// Who used an interaction, and where.
+#[derive(Debug, Clone)]
+struct InteractionSource {
+    user: User,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    permissions: Permissions,
+    roles: Vec<RoleId>,
+}
+
+impl Default for InteractionSource {
+    fn default() -> Self {
+        Self {
+            user: FakeUser::default().build(),
+            guild_id: None,
+            channel_id: ChannelId::new(1),
+            permissions: Permissions::empty(),
+            roles: Vec::new(),
+        }
+    }
+}
+
+impl InteractionSource {
+    /// Returns the payload of an interaction, with its `kind`, `data` and other `fields`.
+    fn to_json(&self, kind: u8, data: Value, fields: Map<String, Value>) -> Value {
+        let id = next_id();
+        let mut interaction = json!({
+            "id": id.to_string(),
+            "application_id": FAKE_APPLICATION_ID.to_string(),
+            "type": kind,
+            "data": data,
+            "channel_id": self.channel_id,
+            "token": format!("fake-interaction-token-{id}"),
+            "version": 1,
+            "app_permissions": Permissions::all(),
+            "locale": "en-US",
+            "entitlements": [],
+            "attachment_size_limit": 0,
+        });
+
+        match self.guild_id {
+            Some(guild_id) => {
+                let mut member = Member::default();
+                member.user = self.user.clone();
+                member.guild_id = guild_id;
+                member.roles = self.roles.clone();
+                member.permissions = Some(self.permissions);
+
+                interaction["guild_id"] = to_value(guild_id);
+                interaction["member"] = to_value(member);
+            }
+            None => interaction["user"] = to_value(&self.user),
+        }
+
+        for (key, value) in fields {
+            interaction[key] = value;
+        }
+
+        interaction
+    }
+}
+
+/// Adds the setters of [`InteractionSource`] to an interaction builder.
+macro_rules! interaction_source_setters {
+    () => {
+        /// Sets who used the interaction, [`FakeUser::default`] by default.
+        pub fn user(mut self, user: User) -> Self {
+            self.source.user = user;
+            self
+        }
+
+        /// Uses the interaction in the guild instead of DMs.
+        pub fn guild(mut self, guild_id: impl Into<GuildId>) -> Self {
+            self.source.guild_id = Some(guild_id.into());
+            self
+        }
+
+        pub fn channel(mut self, channel_id: impl Into<ChannelId>) -> Self {
+            self.source.channel_id = channel_id.into();
+            self
+        }
+
+        /// Sets the permissions of the user in the guild.
+        pub fn permissions(mut self, permissions: Permissions) -> Self {
+            self.source.permissions = permissions;
+            self
+        }
+
+        /// Sets the roles of the user in the guild.
+        pub fn roles(mut self, roles: impl IntoIterator<Item = RoleId>) -> Self {
+            self.source.roles = roles.into_iter().collect();
+            self
+        }
+    };
+}
+
+/// Builds a [`CommandInteraction`], or an autocomplete with [`autocomplete`](Self::autocomplete).
+///
+/// The options are set on the last subcommand of the path, e.g. on `ban` for `admin ban`.
+#[derive(Debug, Clone)]
+pub struct FakeCommand {
+    path: String,
+    options: Vec<Value>,
+    users: Map<String, Value>,
+    autocomplete: bool,
+    source: InteractionSource,
+}
+
+impl FakeCommand {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            options: Vec::new(),
+            users: Map::new(),
+            autocomplete: false,
+            source: InteractionSource::default(),
+        }
+    }
+
+    /// Adds an option, whose type follows the value: string, integer, number or boolean.
+    pub fn option(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let value = value.into();
+        let kind = match &value {
+            Value::Bool(_) => 5,
+            Value::Number(number) if number.is_f64() => 10,
+            Value::Number(_) => 4,
+            _ => 3,
+        };
+
+        self.options
+            .push(json!({ "name": name.into(), "type": kind, "value": value }));
+        self
+    }
+
+    pub fn user_option(mut self, name: impl Into<String>, user: User) -> Self {
+        self.options
+            .push(json!({ "name": name.into(), "type": 6, "value": user.id }));
+        self.users.insert(user.id.to_string(), to_value(user));
+        self
+    }
+
+    /// Turns the interaction into the autocomplete of the string option, with what the user has
+    /// typed so far.
+    pub fn autocomplete(mut self, name: impl Into<String>, input: impl Into<String>) -> Self {
+        self.options.push(json!({
+            "name": name.into(),
+            "type": 3,
+            "value": input.into(),
+            "focused": true,
+        }));
+        self.autocomplete = true;
+        self
+    }
+
+    interaction_source_setters!();
+
+    fn to_json(&self) -> Value {
+        let mut names = self.path.split_whitespace().rev();
+        let name = names.next_back().unwrap_or_default();
+        let mut options = Value::from(self.options.clone());
+
+        // Wraps the options in the subcommand and the subcommand group, from the last one
+        let subcommands: Vec<_> = names.collect();
+        for (index, subcommand) in subcommands.iter().enumerate() {
+            let kind = if index == 0 { 1 } else { 2 };
+            options = json!([{ "name": subcommand, "type": kind, "options": options }]);
+        }
+
+        let data = json!({
+            "id": next_id().to_string(),
+            "name": name,
+            "type": 1,
+            "options": options,
+            "resolved": { "users": self.users },
+        });
+
+        let kind = if self.autocomplete { 4 } else { 2 };
+        self.source.to_json(kind, data, Map::new())
+    }
+
+    /// Returns the command interaction, even if it is an autocomplete.
+    pub fn build(self) -> CommandInteraction {
+        from_value(self.to_json())
+    }
+}
+
+impl From<FakeCommand> for Interaction {
+    fn from(command: FakeCommand) -> Self {
+        from_value(command.to_json())
+    }
+}
+
+/// Builds a [`ComponentInteraction`] for a button or a select menu.
+#[derive(Debug, Clone)]
+pub struct FakeComponent {
+    data: Value,
+    message: SMessage,
+    source: InteractionSource,
+}
+
+impl FakeComponent {
+    pub fn button(custom_id: impl Into<String>) -> Self {
+        Self::new(json!({ "custom_id": custom_id.into(), "component_type": 2 }))
+    }
+
+    pub fn select(
+        custom_id: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let values: Vec<String> = values.into_iter().map(Into::into).collect();
+        Self::new(json!({
+            "custom_id": custom_id.into(),
+            "component_type": 3,
+            "values": values,
+        }))
+    }
+
+    fn new(data: Value) -> Self {
+        Self {
+            data,
+            message: FakeMessage::new("").build(),
+            source: InteractionSource::default(),
+        }
+    }
+
+    /// Sets the message the component is attached to.
+    pub fn message(mut self, message: SMessage) -> Self {
+        self.message = message;
+        self
+    }
+
+    interaction_source_setters!();
+
+    fn to_json(&self) -> Value {
+        let mut fields = Map::new();
+        fields.insert("message".to_string(), to_value(&self.message));
+
+        self.source.to_json(3, self.data.clone(), fields)
+    }
+
+    pub fn build(self) -> ComponentInteraction {
+        from_value(self.to_json())
+    }
+}
+
+impl From<FakeComponent> for Interaction {
+    fn from(component: FakeComponent) -> Self {
+        from_value(component.to_json())
+    }
+}
+
+/// Builds a [`ModalInteraction`] with the values of its text inputs.
+#[derive(Debug, Clone)]
+pub struct FakeModal {
+    custom_id: String,
+    inputs: Vec<(String, String)>,
+    source: InteractionSource,
+}
+
+impl FakeModal {
+    pub fn new(custom_id: impl Into<String>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            inputs: Vec::new(),
+            source: InteractionSource::default(),
+        }
+    }
+
+    pub fn input(mut self, custom_id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.inputs.push((custom_id.into(), value.into()));
+        self
+    }
+
+    interaction_source_setters!();
+
+    fn to_json(&self) -> Value {
+        let rows: Vec<_> = self
+            .inputs
+            .iter()
+            .map(|(custom_id, value)| {
+                json!({
+                    "type": 1,
+                    "components": [{ "type": 4, "custom_id": custom_id, "value": value }],
+                })
+            })
+            .collect();
+
+        let data = json!({ "custom_id": self.custom_id, "components": rows });
+        self.source.to_json(5, data, Map::new())
+    }
+
+    pub fn build(self) -> ModalInteraction {
+        from_value(self.to_json())
+    }
+}
+
+impl From<FakeModal> for Interaction {
+    fn from(modal: FakeModal) -> Self {
+        from_value(modal.to_json())
+    }
+}
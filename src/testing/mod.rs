@@ -0,0 +1,261 @@
+//! Testing the systems of a bot without a connection to discord.
+//!
+//! [`DiscordTestPlugin`] adds [`DiscordBotPlugin`] without connecting it to discord. Events are
+//! injected with [`DiscordTestHarness`] through the same queue as the gateway events, so they
+//! are written as bevy messages on the next update. The messages are built with the fake
+//! payloads of the [`Fake*`](FakeMessage) builders, and a [`Context`] that isn't connected to
+//! discord.
+//!
+//! The HTTP client of the context and of [`DiscordHttpResource`] sends the requests to a
+//! [`MockDiscordServer`], which answers them from its in-memory state and captures them as
+//! [`CapturedRequest`]s. It is returned by [`DiscordTestHarness::server`] to inspect the messages,
+//! commands and interaction responses sent by the bot.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use bevy::prelude::*;
+//! use bevy_discord::DiscordSystems;
+//! use bevy_discord::messages::bot::DiscordMessage;
+//! use bevy_discord::messages::http::DiscordRequest;
+//! use bevy_discord::serenity::all::{ChannelId, CreateMessage};
+//! use bevy_discord::testing::{DiscordTestHarness, DiscordTestPlugin, FakeMessage};
+//!
+//! fn pong(mut messages: MessageReader<DiscordMessage>, mut requests: MessageWriter<DiscordRequest>) {
+//!     for message in messages.read() {
+//!         if message.new_message.content == "ping" {
+//!             requests.write(DiscordRequest::SendMessage {
+//!                 channel_id: message.new_message.channel_id,
+//!                 message: CreateMessage::new().content("pong"),
+//!             });
+//!         }
+//!     }
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins(DiscordTestPlugin::default())
+//!     .add_systems(Update, pong.after(DiscordSystems));
+//!
+//! let harness = app.world().resource::<DiscordTestHarness>().clone();
+//! harness.send_message(FakeMessage::new("ping").channel(7).build());
+//! app.update();
+//!
+//! let requests = harness.requests();
+//! assert_eq!(requests[0].path, "/api/v10/channels/7/messages");
+//! assert_eq!(requests[0].json().unwrap()["content"], "pong");
+//!
+//! let messages = harness.server().messages(ChannelId::new(7));
+//! assert_eq!(messages[0].content, "pong");
+//! ```
+//!
+//! [`DiscordHttpResource`]: crate::res::DiscordHttpResource
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::Resource;
+use serenity::all::{ApplicationId, ChannelId, Context, HttpBuilder, Interaction};
+use serenity::model::channel::Message as SMessage;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+
+use crate::DiscordBotPlugin;
+use crate::bot::handle::EventSender;
+use crate::bot::offline::offline_context;
+use crate::channel::ChannelRes;
+use crate::config::DiscordBotConfig;
+use crate::http::request::CompletedRequests;
+use crate::messages::bot::{DiscordMessage, InteractionCreateMessage};
+use crate::messages::{ContextReaders, MessageCollectionBot};
+use crate::res::DiscordHttpResource;
+use crate::runtime::{PendingRequests, tokio_runtime};
+
+mod fake;
+mod routes;
+mod server;
+
+pub use fake::{
+    FAKE_APPLICATION_ID, FAKE_BOT_USER_ID, FakeCommand, FakeComponent, FakeGuild, FakeMessage,
+    FakeModal, FakeUser,
+};
+pub use server::{CapturedRequest, MockDiscordServer};
+
+/// How long [`DiscordTestHarness::requests`] waits for the pending requests.
+const REQUESTS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Adds [`DiscordBotPlugin`] without connecting to discord, see the [module](self) for more
+/// information.
+///
+/// The connection state is [`Ready`](crate::state::DiscordConnectionState::Ready) from the first
+/// update.
+///
+/// # Panics
+///
+/// Panics if it is added from an async runtime, or if the HTTP server can't be started.
+#[derive(Debug, Clone, Default)]
+pub struct DiscordTestPlugin {
+    config: DiscordBotConfig,
+}
+
+impl DiscordTestPlugin {
+    /// Sets the config of [`DiscordBotPlugin`], the gateway settings aren't used.
+    pub fn config(mut self, config: DiscordBotConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl Plugin for DiscordTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(DiscordBotPlugin::new(self.config.clone()));
+
+        let tx = EventSender::new(
+            app.world()
+                .resource::<ChannelRes<MessageCollectionBot>>()
+                .tx
+                .clone(),
+            &self.config,
+            app.world().resource::<ContextReaders>().clone(),
+        );
+        let server = MockDiscordServer::start().expect("the mock server should start");
+
+        let http = HttpBuilder::new("fake-token")
+            .proxy(server.url())
+            .ratelimiter_disabled(true)
+            .application_id(ApplicationId::new(FAKE_APPLICATION_ID))
+            .build();
+        let ctx = tokio_runtime()
+            .block_on(offline_context(Arc::new(http)))
+            .expect("the offline context should be built");
+
+        let world = app.world();
+        let pending = world.resource::<PendingRequests>().clone();
+        let http = world
+            .resource::<CompletedRequests>()
+            .connect(&pending, DiscordHttpResource::new(ctx.http.clone()));
+
+        app.insert_resource(http)
+            .insert_resource(DiscordTestHarness {
+                ctx,
+                tx,
+                server,
+                pending,
+            });
+    }
+}
+
+/// Injects fake events and inspects the requests sent to discord, inserted by
+/// [`DiscordTestPlugin`].
+#[derive(Resource, Clone)]
+pub struct DiscordTestHarness {
+    ctx: Context,
+    tx: EventSender,
+    server: MockDiscordServer,
+    /// Requests and tasks of the app the harness was inserted in.
+    pending: PendingRequests,
+}
+
+impl DiscordTestHarness {
+    /// Returns the context of the injected events, to build the messages sent with
+    /// [`send`](Self::send).
+    pub fn ctx(&self) -> Context {
+        self.ctx.clone()
+    }
+
+    /// Queues any message of [`messages::bot`](crate::messages::bot) or
+    /// [`messages::payload`](crate::messages::payload), which is written on the next update.
+    ///
+    /// The gateway messages are converted according to the
+    /// [`BotMessageMode`](crate::config::BotMessageMode) of the config.
+    pub fn send(&self, message: impl InjectableMessage) {
+        let message = message.into_queued().0;
+
+        if tokio_runtime()
+            .block_on(self.tx.send_async(message))
+            .is_err()
+        {
+            error!("Unable to send event to the channel")
+        }
+    }
+
+    /// Queues a [`DiscordMessage`].
+    pub fn send_message(&self, message: SMessage) {
+        self.send(DiscordMessage {
+            ctx: self.ctx(),
+            new_message: message,
+        });
+    }
+
+    /// Queues an [`InteractionCreateMessage`], built with [`FakeCommand`], [`FakeComponent`] or
+    /// [`FakeModal`].
+    ///
+    /// The responses to it are sent in its channel, see
+    /// [`MockDiscordServer::interaction_messages`].
+    pub fn send_interaction(&self, interaction: impl Into<Interaction>) {
+        let interaction = interaction.into();
+        if let Some((token, channel_id)) = interaction_channel(&interaction) {
+            self.server.register_interaction(token, channel_id);
+        }
+
+        self.send(InteractionCreateMessage {
+            ctx: self.ctx(),
+            interaction,
+        });
+    }
+
+    /// Returns the server the requests are sent to.
+    pub fn server(&self) -> &MockDiscordServer {
+        &self.server
+    }
+
+    /// Waits for the pending requests of the app to be sent, for up to 5 seconds, and returns
+    /// all the requests captured so far.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        tokio_runtime().block_on(async {
+            let _ = tokio::time::timeout(REQUESTS_TIMEOUT, self.pending.finished()).await;
+        });
+
+        self.server.requests()
+    }
+
+    /// Like [`requests`](Self::requests), but forgets the returned requests.
+    pub fn take_requests(&self) -> Vec<CapturedRequest> {
+        self.requests();
+        self.server.take_requests()
+    }
+}
+
+/// Returns the token and channel of the interactions that can be responded to.
+fn interaction_channel(interaction: &Interaction) -> Option<(String, ChannelId)> {
+    match interaction {
+        Interaction::Command(command) | Interaction::Autocomplete(command) => {
+            Some((command.token.clone(), command.channel_id))
+        }
+        Interaction::Component(component) => Some((component.token.clone(), component.channel_id)),
+        Interaction::Modal(modal) => Some((modal.token.clone(), modal.channel_id)),
+        _ => None,
+    }
+}
+
+mod sealed {
+    use crate::messages::MessageCollectionBot;
+
+    /// Message as it is queued, which isn't public.
+    pub struct Queued(pub(crate) MessageCollectionBot);
+
+    pub trait Sealed {
+        fn into_queued(self) -> Queued;
+    }
+
+    impl<T: Into<MessageCollectionBot>> Sealed for T {
+        fn into_queued(self) -> Queued {
+            Queued(self.into())
+        }
+    }
+}
+
+/// Messages of [`messages::bot`](crate::messages::bot) and
+/// [`messages::payload`](crate::messages::payload) that can be sent with
+/// [`DiscordTestHarness::send`].
+pub trait InjectableMessage: sealed::Sealed {}
+
+impl<T: sealed::Sealed> InjectableMessage for T {}
@@ -0,0 +1,681 @@
+use serde_json::{Map, Value, json};
+use serenity::all::{Command, GuildChannel, Timestamp};
+use serenity::model::channel::Message as SMessage;
+use std::collections::HashMap;
+
+use super::fake::{FAKE_BOT_USER_ID, FakeUser, from_value, to_value};
+use super::server::CapturedRequest;
+
+/// Fields of a message that are taken from the body of the requests.
+const MESSAGE_FIELDS: &[&str] = &[
+    "content",
+    "embeds",
+    "components",
+    "tts",
+    "flags",
+    "message_reference",
+];
+
+/// Fields of a channel that are taken from the body of the requests.
+const CHANNEL_FIELDS: &[&str] = &[
+    "name",
+    "type",
+    "topic",
+    "nsfw",
+    "position",
+    "parent_id",
+    "rate_limit_per_user",
+    "bitrate",
+    "user_limit",
+];
+
+/// Number of messages returned by default when getting the messages of a channel.
+const DEFAULT_MESSAGES_LIMIT: usize = 50;
+
+/// Response of the mock server.
+pub(super) enum Response {
+    /// `204 No Content`
+    Empty,
+    Json(u16, Value),
+}
+
+fn ok(value: Value) -> Response {
+    Response::Json(200, value)
+}
+
+/// Error in the format of discord's JSON errors.
+fn error(status: u16, code: u32, message: &str) -> Response {
+    Response::Json(status, json!({ "code": code, "message": message }))
+}
+
+fn unknown_channel() -> Response {
+    error(404, 10003, "Unknown Channel")
+}
+
+fn unknown_message() -> Response {
+    error(404, 10008, "Unknown Message")
+}
+
+fn unknown_command() -> Response {
+    error(404, 10063, "Unknown application command")
+}
+
+/// Parses an id of the path, invalid ids are turned into an id that is never used.
+fn parse_id(id: &str) -> u64 {
+    id.parse().unwrap_or_default()
+}
+
+/// Decodes the percent-encoded emojis of the reaction routes.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Copies the `fields` of the body to the payload.
+fn merge(target: &mut Value, body: &Value, fields: &[&str]) {
+    for field in fields {
+        if let Some(value) = body.get(field) {
+            target[*field] = value.clone();
+        }
+    }
+}
+
+/// Messages sent in response to an interaction.
+#[derive(Debug, Default)]
+struct InteractionMessages {
+    original: Option<Value>,
+    followups: Vec<Value>,
+}
+
+/// State of [`MockDiscordServer`](super::MockDiscordServer), the payloads are kept as JSON so that
+/// they are returned as they were sent.
+#[derive(Debug)]
+pub(super) struct MockState {
+    pub(super) requests: Vec<CapturedRequest>,
+    next_id: u64,
+    /// Messages of each channel, oldest first.
+    messages: HashMap<u64, Vec<Value>>,
+    pub(super) reactions: HashMap<(u64, u64), Vec<String>>,
+    channels: HashMap<u64, Value>,
+    /// Commands of each guild, and the global ones under `None`.
+    commands: HashMap<Option<u64>, Vec<Value>>,
+    pub(super) interaction_responses: HashMap<u64, Vec<Value>>,
+    /// Channel of the interactions, by token.
+    interaction_channels: HashMap<String, u64>,
+    interaction_messages: HashMap<String, InteractionMessages>,
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        Self {
+            requests: Vec::new(),
+            // Far from the ids of the fake payloads
+            next_id: 1_000_000,
+            messages: HashMap::new(),
+            reactions: HashMap::new(),
+            channels: HashMap::new(),
+            commands: HashMap::new(),
+            interaction_responses: HashMap::new(),
+            interaction_channels: HashMap::new(),
+            interaction_messages: HashMap::new(),
+        }
+    }
+}
+
+impl MockState {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub(super) fn messages(&self, channel_id: u64) -> Vec<SMessage> {
+        self.messages
+            .get(&channel_id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .map(from_value)
+            .collect()
+    }
+
+    pub(super) fn insert_message(&mut self, message: SMessage) {
+        let messages = self.messages.entry(message.channel_id.get()).or_default();
+        let id = message.id.get().to_string();
+        let message = to_value(message);
+
+        match messages.iter_mut().find(|stored| stored["id"] == id) {
+            Some(stored) => *stored = message,
+            None => messages.push(message),
+        }
+    }
+
+    pub(super) fn channel(&self, channel_id: u64) -> Option<GuildChannel> {
+        self.channels.get(&channel_id).cloned().map(from_value)
+    }
+
+    pub(super) fn insert_channel(&mut self, channel: GuildChannel) {
+        self.channels.insert(channel.id.get(), to_value(channel));
+    }
+
+    pub(super) fn commands(&self, guild_id: Option<u64>) -> Vec<Command> {
+        self.commands
+            .get(&guild_id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .map(from_value)
+            .collect()
+    }
+
+    /// Remembers the channel of an interaction, for the messages sent in response to it.
+    pub(super) fn register_interaction(&mut self, token: String, channel_id: u64) {
+        self.interaction_channels.insert(token, channel_id);
+    }
+
+    pub(super) fn interaction_messages(&self, token: &str) -> Vec<SMessage> {
+        self.interaction_messages
+            .get(token)
+            .into_iter()
+            .flat_map(|messages| messages.original.iter().chain(&messages.followups))
+            .cloned()
+            .map(from_value)
+            .collect()
+    }
+
+    /// Answers the request and keeps it.
+    pub(super) fn handle(&mut self, request: CapturedRequest) -> Response {
+        let (path, query) = request
+            .path
+            .split_once('?')
+            .unwrap_or((request.path.as_str(), ""));
+        let path = path.strip_prefix("/api/v10").unwrap_or(path);
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let query: HashMap<&str, &str> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        // Requests with attachments are sent as multipart forms, which aren't parsed
+        let body = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+
+        let response = self.route(&request.method, &segments, &query, &body);
+
+        self.requests.push(request);
+        response
+    }
+
+    fn route(
+        &mut self,
+        method: &str,
+        segments: &[&str],
+        query: &HashMap<&str, &str>,
+        body: &Value,
+    ) -> Response {
+        match (method, segments) {
+            ("GET", ["users", "@me"]) => ok(self.bot_user()),
+            ("GET", ["gateway", "bot"]) => ok(json!({
+                "url": "wss://gateway.discord.gg",
+                "shards": 1,
+                "session_start_limit": {
+                    "total": 1000,
+                    "remaining": 1000,
+                    "reset_after": 0,
+                    "max_concurrency": 1,
+                },
+            })),
+
+            ("GET", ["channels", channel]) => match self.channels.get(&parse_id(channel)) {
+                Some(channel) => ok(channel.clone()),
+                None => unknown_channel(),
+            },
+            ("PATCH", ["channels", channel]) => match self.channels.get_mut(&parse_id(channel)) {
+                Some(channel) => {
+                    merge(channel, body, CHANNEL_FIELDS);
+                    ok(channel.clone())
+                }
+                None => unknown_channel(),
+            },
+            ("DELETE", ["channels", channel]) => match self.channels.remove(&parse_id(channel)) {
+                Some(channel) => ok(channel),
+                None => unknown_channel(),
+            },
+            ("POST", ["guilds", guild, "channels"]) => {
+                let id = self.next_id();
+                ok(self.create_channel(id, parse_id(guild), body))
+            }
+            ("POST", ["channels", channel, "threads"]) => {
+                let id = self.next_id();
+                ok(self.create_thread(id, parse_id(channel), body))
+            }
+            ("POST", ["channels", channel, "messages", message, "threads"]) => {
+                // Threads started from a message have the same id as the message
+                ok(self.create_thread(parse_id(message), parse_id(channel), body))
+            }
+
+            ("GET", ["channels", channel, "messages"]) => {
+                let limit = query
+                    .get("limit")
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(DEFAULT_MESSAGES_LIMIT);
+                let before = query.get("before").map(|id| parse_id(id));
+                let after = query.get("after").map(|id| parse_id(id));
+
+                let messages = self
+                    .messages
+                    .get(&parse_id(channel))
+                    .into_iter()
+                    .flatten()
+                    .rev()
+                    .filter(|message| {
+                        let id = message["id"].as_str().map(parse_id).unwrap_or_default();
+                        before.is_none_or(|before| id < before)
+                            && after.is_none_or(|after| id > after)
+                    })
+                    .take(limit)
+                    .cloned()
+                    .collect();
+
+                ok(Value::Array(messages))
+            }
+            ("POST", ["channels", channel, "messages"]) => {
+                let channel_id = parse_id(channel);
+                let message = self.new_message(channel_id, body);
+                self.messages
+                    .entry(channel_id)
+                    .or_default()
+                    .push(message.clone());
+                ok(message)
+            }
+            ("GET", ["channels", channel, "messages", message]) => {
+                match self.message(parse_id(channel), message) {
+                    Some(message) => ok(message.clone()),
+                    None => unknown_message(),
+                }
+            }
+            ("PATCH", ["channels", channel, "messages", message]) => {
+                match self.message(parse_id(channel), message) {
+                    Some(message) => ok(edit_message(message, body)),
+                    None => unknown_message(),
+                }
+            }
+            ("DELETE", ["channels", channel, "messages", message]) => {
+                let messages = self.messages.entry(parse_id(channel)).or_default();
+                let len = messages.len();
+                messages.retain(|stored| stored["id"] != *message);
+
+                if messages.len() == len {
+                    unknown_message()
+                } else {
+                    Response::Empty
+                }
+            }
+
+            (
+                "PUT",
+                [
+                    "channels",
+                    channel,
+                    "messages",
+                    message,
+                    "reactions",
+                    emoji,
+                    "@me",
+                ],
+            ) => {
+                let emoji = percent_decode(emoji);
+                let reactions = self
+                    .reactions
+                    .entry((parse_id(channel), parse_id(message)))
+                    .or_default();
+
+                if !reactions.contains(&emoji) {
+                    reactions.push(emoji);
+                }
+                Response::Empty
+            }
+            (
+                "DELETE",
+                [
+                    "channels",
+                    channel,
+                    "messages",
+                    message,
+                    "reactions",
+                    emoji,
+                    "@me",
+                ],
+            ) => {
+                let emoji = percent_decode(emoji);
+                if let Some(reactions) = self
+                    .reactions
+                    .get_mut(&(parse_id(channel), parse_id(message)))
+                {
+                    reactions.retain(|reaction| *reaction != emoji);
+                }
+                Response::Empty
+            }
+            ("DELETE", ["channels", channel, "messages", message, "reactions"]) => {
+                self.reactions
+                    .remove(&(parse_id(channel), parse_id(message)));
+                Response::Empty
+            }
+
+            (method, ["applications", application, "commands", rest @ ..]) => {
+                self.route_commands(method, parse_id(application), None, rest, body)
+            }
+            (
+                method,
+                [
+                    "applications",
+                    application,
+                    "guilds",
+                    guild,
+                    "commands",
+                    rest @ ..,
+                ],
+            ) => self.route_commands(
+                method,
+                parse_id(application),
+                Some(parse_id(guild)),
+                rest,
+                body,
+            ),
+
+            ("POST", ["interactions", interaction, token, "callback"]) => {
+                self.respond_to_interaction(parse_id(interaction), token, body)
+            }
+            ("POST", ["webhooks", _, token]) => {
+                let message = self.new_message(self.interaction_channel(token), body);
+                self.interaction_messages
+                    .entry(token.to_string())
+                    .or_default()
+                    .followups
+                    .push(message.clone());
+                ok(message)
+            }
+            (method, ["webhooks", _, token, "messages", message]) => {
+                self.route_interaction_message(method, token, message, body)
+            }
+
+            _ => error(404, 0, "404: Not Found"),
+        }
+    }
+
+    fn route_commands(
+        &mut self,
+        method: &str,
+        application_id: u64,
+        guild_id: Option<u64>,
+        rest: &[&str],
+        body: &Value,
+    ) -> Response {
+        match (method, rest) {
+            ("GET", []) => ok(Value::Array(
+                self.commands.get(&guild_id).cloned().unwrap_or_default(),
+            )),
+            ("POST", []) => {
+                let command = self.upsert_command(application_id, guild_id, body);
+                ok(command)
+            }
+            ("PUT", []) => {
+                let previous = self.commands.remove(&guild_id).unwrap_or_default();
+                let mut commands = Vec::new();
+
+                for body in body.as_array().into_iter().flatten() {
+                    let id = previous
+                        .iter()
+                        .find(|command| command["name"] == body["name"])
+                        .and_then(|command| command["id"].as_str())
+                        .map(parse_id);
+                    commands.push(self.new_command(id, application_id, guild_id, body));
+                }
+
+                self.commands.insert(guild_id, commands.clone());
+                ok(Value::Array(commands))
+            }
+            (method, [command]) => {
+                let version = self.next_id();
+                let commands = self.commands.entry(guild_id).or_default();
+                let Some(position) = commands.iter().position(|stored| stored["id"] == *command)
+                else {
+                    return unknown_command();
+                };
+
+                match method {
+                    "GET" => ok(commands[position].clone()),
+                    "PATCH" => {
+                        let command = &mut commands[position];
+                        if let Some(body) = body.as_object() {
+                            for (key, value) in body {
+                                command[key] = value.clone();
+                            }
+                        }
+                        command["version"] = json!(version.to_string());
+                        ok(command.clone())
+                    }
+                    "DELETE" => {
+                        commands.remove(position);
+                        Response::Empty
+                    }
+                    _ => error(405, 0, "405: Method Not Allowed"),
+                }
+            }
+            _ => error(404, 0, "404: Not Found"),
+        }
+    }
+
+    fn route_interaction_message(
+        &mut self,
+        method: &str,
+        token: &str,
+        message: &str,
+        body: &Value,
+    ) -> Response {
+        let messages = self
+            .interaction_messages
+            .entry(token.to_string())
+            .or_default();
+        let stored = if message == "@original" {
+            messages.original.as_mut()
+        } else {
+            messages
+                .followups
+                .iter_mut()
+                .find(|stored| stored["id"] == *message)
+        };
+
+        let Some(stored) = stored else {
+            return unknown_message();
+        };
+
+        match method {
+            "GET" => ok(stored.clone()),
+            "PATCH" => ok(edit_message(stored, body)),
+            "DELETE" => {
+                if message == "@original" {
+                    messages.original = None;
+                } else {
+                    messages.followups.retain(|stored| stored["id"] != *message);
+                }
+                Response::Empty
+            }
+            _ => error(405, 0, "405: Method Not Allowed"),
+        }
+    }
+
+    fn respond_to_interaction(
+        &mut self,
+        interaction_id: u64,
+        token: &str,
+        body: &Value,
+    ) -> Response {
+        let responses = self
+            .interaction_responses
+            .entry(interaction_id)
+            .or_default();
+        if !responses.is_empty() {
+            return error(400, 40060, "Interaction has already been acknowledged.");
+        }
+        responses.push(body.clone());
+
+        let original = match body["type"].as_u64() {
+            // Channel message
+            Some(4) => &body["data"],
+            // Deferred channel message, shown as loading until it is edited
+            Some(5) => &json!({ "flags": body["data"]["flags"] }),
+            _ => return Response::Empty,
+        };
+
+        let message = self.new_message(self.interaction_channel(token), original);
+        self.interaction_messages
+            .entry(token.to_string())
+            .or_default()
+            .original = Some(message);
+
+        Response::Empty
+    }
+
+    fn interaction_channel(&self, token: &str) -> u64 {
+        self.interaction_channels.get(token).copied().unwrap_or(1)
+    }
+
+    fn bot_user(&self) -> Value {
+        to_value(FakeUser::new(FAKE_BOT_USER_ID).name("bot").bot().build())
+    }
+
+    fn message(&mut self, channel_id: u64, message_id: &str) -> Option<&mut Value> {
+        self.messages
+            .get_mut(&channel_id)?
+            .iter_mut()
+            .find(|message| message["id"] == message_id)
+    }
+
+    /// Builds a message sent by the bot.
+    fn new_message(&mut self, channel_id: u64, body: &Value) -> Value {
+        let mut message = to_value(SMessage::default());
+        message["id"] = json!(self.next_id().to_string());
+        message["channel_id"] = json!(channel_id.to_string());
+        message["author"] = self.bot_user();
+        message["timestamp"] = to_value(Timestamp::now());
+        merge(&mut message, body, MESSAGE_FIELDS);
+
+        if !body["message_reference"].is_null() {
+            // Reply
+            message["type"] = json!(19);
+        }
+
+        message
+    }
+
+    fn create_channel(&mut self, id: u64, guild_id: u64, body: &Value) -> Value {
+        let mut channel = to_value(GuildChannel::default());
+        channel["id"] = json!(id.to_string());
+        channel["guild_id"] = json!(guild_id.to_string());
+        merge(&mut channel, body, CHANNEL_FIELDS);
+
+        self.channels.insert(id, channel.clone());
+        channel
+    }
+
+    /// Creates a public thread, unless the body sets its type.
+    fn create_thread(&mut self, id: u64, parent_id: u64, body: &Value) -> Value {
+        let guild_id = self
+            .channels
+            .get(&parent_id)
+            .and_then(|parent| parent["guild_id"].as_str())
+            .map(parse_id)
+            .unwrap_or(1);
+
+        let mut body = body.clone();
+        body["parent_id"] = json!(parent_id.to_string());
+        if body["type"].is_null() {
+            body["type"] = json!(11);
+        }
+
+        self.create_channel(id, guild_id, &body)
+    }
+
+    /// Creates a command, or replaces the one with the same name.
+    fn upsert_command(
+        &mut self,
+        application_id: u64,
+        guild_id: Option<u64>,
+        body: &Value,
+    ) -> Value {
+        let commands = self.commands.entry(guild_id).or_default();
+        let position = commands
+            .iter()
+            .position(|command| command["name"] == body["name"]);
+        let id = position
+            .and_then(|position| commands[position]["id"].as_str())
+            .map(parse_id);
+
+        let command = self.new_command(id, application_id, guild_id, body);
+        let commands = self.commands.entry(guild_id).or_default();
+        match position {
+            Some(position) => commands[position] = command.clone(),
+            None => commands.push(command.clone()),
+        }
+
+        command
+    }
+
+    fn new_command(
+        &mut self,
+        id: Option<u64>,
+        application_id: u64,
+        guild_id: Option<u64>,
+        body: &Value,
+    ) -> Value {
+        let mut command = Value::Object(Map::new());
+        if let Some(body) = body.as_object() {
+            for (key, value) in body {
+                command[key] = value.clone();
+            }
+        }
+
+        let id = id.unwrap_or_else(|| self.next_id());
+        command["id"] = json!(id.to_string());
+        command["application_id"] = json!(application_id.to_string());
+        command["version"] = json!(self.next_id().to_string());
+        if let Some(guild_id) = guild_id {
+            command["guild_id"] = json!(guild_id.to_string());
+        }
+        if command["type"].is_null() {
+            command["type"] = json!(1);
+        }
+        if command["description"].is_null() {
+            // Context menu commands have an empty description
+            command["description"] = json!("");
+        }
+
+        command
+    }
+}
+
+/// Edits a message sent by the bot.
+fn edit_message(message: &mut Value, body: &Value) -> Value {
+    merge(message, body, MESSAGE_FIELDS);
+    message["edited_timestamp"] = to_value(Timestamp::now());
+    message.clone()
+}
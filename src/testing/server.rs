@@ -0,0 +1,357 @@
+use serde_json::Value;
+use serenity::all::{
+    ChannelId, Command, GuildChannel, GuildId, InteractionId, Message as SMessage, MessageId,
+};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::error;
+
+use super::routes::{MockState, Response};
+use crate::runtime::tokio_runtime;
+
+/// Request sent to the Discord API, captured by [`MockDiscordServer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedRequest {
+    /// Method of the request, e.g. `POST`.
+    pub method: String,
+    /// Path of the request with its query, e.g. `/api/v10/channels/1/messages`.
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl CapturedRequest {
+    /// Parses the body as JSON, returns `None` if it is empty or isn't JSON, e.g. for the
+    /// requests with attachments.
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+/// Stand-in for the Discord REST API on localhost, keeping what is sent to it in memory.
+///
+/// Point the HTTP clients at it with [`DiscordHttpBaseUrl`](crate::res::DiscordHttpBaseUrl) or
+/// [`DiscordBotConfig::base_url`](crate::config::DiscordBotConfig::base_url), it is already used
+/// by [`DiscordTestPlugin`](super::DiscordTestPlugin).
+///
+/// The following requests are implemented, and any other one is answered with a `404`:
+/// - Getting, sending, editing and deleting the messages of any channel
+/// - Adding and removing the reactions of the bot
+/// - Getting, creating, editing and deleting channels, and creating threads
+/// - Getting, creating, editing, overwriting and deleting global and guild commands
+/// - Responding to interactions, and their original response and follow-ups
+/// - Getting the current user and the URL of the gateway
+///
+/// The gateway itself isn't mocked, a bot pointed at the server still connects to discord's
+/// gateway. The server runs until the process exits.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_discord::DiscordHttpPlugin;
+/// use bevy_discord::res::DiscordHttpBaseUrl;
+/// use bevy_discord::testing::MockDiscordServer;
+///
+/// let server = MockDiscordServer::start().unwrap();
+///
+/// App::new()
+///     .insert_resource(DiscordHttpBaseUrl(server.url().to_string()))
+///     .add_plugins(DiscordHttpPlugin::new("token".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockDiscordServer {
+    url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockDiscordServer {
+    /// Starts the server on a free port of localhost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called from an async runtime.
+    pub fn start() -> std::io::Result<Self> {
+        let listener =
+            tokio_runtime().block_on(async { TcpListener::bind("127.0.0.1:0").await })?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        tokio_runtime().spawn(serve(listener, state.clone()));
+
+        Ok(Self { url, state })
+    }
+
+    /// Returns the URL to use as base URL, e.g. `http://127.0.0.1:41233`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .expect("mock server lock shouldn't be poisoned")
+    }
+
+    /// Returns all the requests received so far.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.state().requests.clone()
+    }
+
+    /// Like [`requests`](Self::requests), but forgets the returned requests.
+    pub fn take_requests(&self) -> Vec<CapturedRequest> {
+        std::mem::take(&mut self.state().requests)
+    }
+
+    /// Returns the messages of the channel, oldest first.
+    pub fn messages(&self, channel_id: ChannelId) -> Vec<SMessage> {
+        self.state().messages(channel_id.get())
+    }
+
+    /// Adds a message to its channel, e.g. one built with [`FakeMessage`](super::FakeMessage).
+    pub fn insert_message(&self, message: SMessage) {
+        self.state().insert_message(message);
+    }
+
+    /// Returns the reactions of the bot to the message, e.g. `👍` or `name:123`.
+    pub fn reactions(&self, channel_id: ChannelId, message_id: MessageId) -> Vec<String> {
+        self.state()
+            .reactions
+            .get(&(channel_id.get(), message_id.get()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn channel(&self, channel_id: ChannelId) -> Option<GuildChannel> {
+        self.state().channel(channel_id.get())
+    }
+
+    /// Adds a channel, replacing the one with the same id.
+    pub fn insert_channel(&self, channel: GuildChannel) {
+        self.state().insert_channel(channel);
+    }
+
+    /// Returns the commands of the guild, or the global ones for `None`.
+    pub fn commands(&self, guild_id: Option<GuildId>) -> Vec<Command> {
+        self.state().commands(guild_id.map(GuildId::get))
+    }
+
+    /// Returns the responses to the interaction, with their `type` and `data`. Only the first
+    /// one is accepted, as on discord.
+    pub fn interaction_responses(&self, interaction_id: InteractionId) -> Vec<Value> {
+        self.state()
+            .interaction_responses
+            .get(&interaction_id.get())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Sends the responses to the interaction with this token in the channel, instead of the
+    /// channel `1`.
+    pub(super) fn register_interaction(&self, token: String, channel_id: ChannelId) {
+        self.state().register_interaction(token, channel_id.get());
+    }
+
+    /// Returns the original response to the interaction followed by its follow-ups, which aren't
+    /// part of the [`messages`](Self::messages) of their channel.
+    pub fn interaction_messages(&self, token: &str) -> Vec<SMessage> {
+        self.state().interaction_messages(token)
+    }
+}
+
+/// Serves the requests on the listener.
+async fn serve(listener: TcpListener, state: Arc<Mutex<MockState>>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                error!("Unable to accept a request to the mock server: {}", err);
+                continue;
+            }
+        };
+
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(err) = serve_connection(stream, &state).await {
+                error!("Unable to answer a request to the mock server: {}", err);
+            }
+        });
+    }
+}
+
+/// Answers the HTTP/1.1 requests of a connection until it is closed.
+async fn serve_connection(stream: TcpStream, state: &Mutex<MockState>) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+
+    loop {
+        let mut request_line = String::new();
+        if stream.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut content_length = 0;
+        let mut chunked = false;
+
+        loop {
+            let mut header = String::new();
+            stream.read_line(&mut header).await?;
+            let header = header.trim_end();
+
+            if header.is_empty() {
+                break;
+            }
+
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or_default();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+
+        let body = if chunked {
+            read_chunked(&mut stream).await?
+        } else {
+            let mut body = vec![0; content_length];
+            stream.read_exact(&mut body).await?;
+            body
+        };
+
+        let request = CapturedRequest { method, path, body };
+        let response = state
+            .lock()
+            .expect("mock server lock shouldn't be poisoned")
+            .handle(request);
+
+        let bytes = match response {
+            Response::Empty => b"HTTP/1.1 204 No Content\r\n\r\n".to_vec(),
+            Response::Json(status, body) => {
+                let body = body.to_string();
+                format!(
+                    "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                    reason(status),
+                    body.len(),
+                )
+                .into_bytes()
+            }
+        };
+
+        stream.get_mut().write_all(&bytes).await?;
+    }
+}
+
+async fn read_chunked(stream: &mut BufReader<TcpStream>) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+
+    loop {
+        let mut size = String::new();
+        stream.read_line(&mut size).await?;
+        let size = usize::from_str_radix(size.trim(), 16)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        // Every chunk, including the last empty one, ends with a line break
+        let mut chunk = vec![0; size + 2];
+        stream.read_exact(&mut chunk).await?;
+        chunk.truncate(size);
+
+        if size == 0 {
+            return Ok(body);
+        }
+        body.extend(chunk);
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sends the raw requests on one connection, and returns the raw responses.
+    fn exchange(server: &MockDiscordServer, requests: &[u8], responses: usize) -> String {
+        let address = server.url().trim_start_matches("http://").to_string();
+        let requests = requests.to_vec();
+
+        tokio_runtime().block_on(async move {
+            let stream = TcpStream::connect(address).await.unwrap();
+            let mut stream = BufReader::new(stream);
+            stream.get_mut().write_all(&requests).await.unwrap();
+
+            let mut received = String::new();
+            for _ in 0..responses {
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).await.unwrap();
+                    if let Some(length) = line.strip_prefix("Content-Length: ") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    received.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+
+                let mut body = vec![0; content_length];
+                stream.read_exact(&mut body).await.unwrap();
+                received.push_str(&String::from_utf8(body).unwrap());
+            }
+            received
+        })
+    }
+
+    #[test]
+    fn requests_on_one_connection_are_parsed() {
+        let server = MockDiscordServer::start().unwrap();
+        let first = r#"{"content":"first"}"#;
+
+        let responses = exchange(
+            &server,
+            format!(
+                "POST /api/v10/channels/7/messages HTTP/1.1\r\ncontent-length: {}\r\n\r\n{first}\
+                 POST /api/v10/channels/7/messages HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+                 9\r\n{{\"content\r\n\
+                 b\r\n\":\"second\"}}\r\n\
+                 0\r\n\r\n\
+                 GET /api/v10/unknown HTTP/1.1\r\n\r\n",
+                first.len()
+            )
+            .as_bytes(),
+            3,
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].json().unwrap()["content"], "first");
+        assert_eq!(requests[1].json().unwrap()["content"], "second");
+        assert_eq!(
+            (requests[2].method.as_str(), requests[2].path.as_str()),
+            ("GET", "/api/v10/unknown")
+        );
+        assert!(requests[2].body.is_empty());
+
+        let contents: Vec<_> = server
+            .messages(ChannelId::new(7))
+            .into_iter()
+            .map(|message| message.content)
+            .collect();
+        assert_eq!(contents, ["first", "second"]);
+        assert!(responses.contains("HTTP/1.1 404 Not Found"));
+    }
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "bot")]
+
+use bevy_discord::serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, UserId,
+};
+use bevy_discord::slash_commands::{CommandParseError, DiscordCommand};
+use serde_json::{Value, json};
+
+#[derive(DiscordCommand, Debug, PartialEq)]
+#[command(name = "ban", description = "Bans a player")]
+struct BanPlayer {
+    #[option(description = "Number of days", min = -7, max = 30)]
+    days: Option<i64>,
+    #[option(description = "Player to ban")]
+    user: UserId,
+    #[option(name = "why", description = "Reason", choice("Spam", "spam"))]
+    reason: String,
+    #[option(description = "Fine", min = 0.5)]
+    fine: Option<f64>,
+}
+
+fn option(name: &str, value: CommandDataOptionValue) -> CommandDataOption {
+    serde_json::from_value(json!({
+        "name": name,
+        "type": value.kind(),
+        "value": match value {
+            CommandDataOptionValue::Integer(value) => json!(value),
+            CommandDataOptionValue::String(value) => json!(value),
+            CommandDataOptionValue::User(value) => json!(value.to_string()),
+            _ => unreachable!(),
+        },
+    }))
+    .unwrap()
+}
+
+fn options() -> Vec<Value> {
+    let command = serde_json::to_value(BanPlayer::create_command()).unwrap();
+    command["options"].as_array().unwrap().clone()
+}
+
+#[test]
+fn command_is_described_by_the_attributes() {
+    let command = serde_json::to_value(BanPlayer::create_command()).unwrap();
+
+    assert_eq!(BanPlayer::NAME, "ban");
+    assert_eq!(command["name"], "ban");
+    assert_eq!(command["description"], "Bans a player");
+}
+
+#[test]
+fn required_options_come_first() {
+    let names: Vec<_> = options()
+        .iter()
+        .map(|option| (option["name"].clone(), option["required"].clone()))
+        .collect();
+
+    assert_eq!(
+        names,
+        [
+            (json!("user"), json!(true)),
+            (json!("why"), json!(true)),
+            (json!("days"), json!(false)),
+            (json!("fine"), json!(false)),
+        ]
+    );
+}
+
+#[test]
+fn options_have_their_type_and_bounds() {
+    let options = options();
+
+    assert_eq!(options[0]["type"], json!(u8::from(CommandOptionType::User)));
+    assert_eq!(options[1]["choices"][0]["value"], "spam");
+    assert_eq!(
+        options[2]["type"],
+        json!(u8::from(CommandOptionType::Integer))
+    );
+    assert_eq!(options[2]["min_value"].as_f64(), Some(-7.0));
+    assert_eq!(options[2]["max_value"].as_u64(), Some(30));
+    assert_eq!(options[3]["min_value"].as_f64(), Some(0.5));
+}
+
+#[test]
+fn options_are_parsed_by_name() {
+    let parsed = BanPlayer::from_options(&[
+        option("why", CommandDataOptionValue::String("spam".to_string())),
+        option("user", CommandDataOptionValue::User(UserId::new(5))),
+        option("days", CommandDataOptionValue::Integer(3)),
+    ]);
+
+    assert_eq!(
+        parsed,
+        Ok(BanPlayer {
+            days: Some(3),
+            user: UserId::new(5),
+            reason: "spam".to_string(),
+            fine: None,
+        })
+    );
+}
+
+#[test]
+fn missing_and_invalid_options_are_errors() {
+    assert_eq!(
+        BanPlayer::from_options(&[option("user", CommandDataOptionValue::User(UserId::new(5)))]),
+        Err(CommandParseError::MissingOption("why".to_string()))
+    );
+    assert_eq!(
+        BanPlayer::from_options(&[option("user", CommandDataOptionValue::Integer(5))]),
+        Err(CommandParseError::InvalidOption("user".to_string()))
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "bot")]
+
+use bevy_discord::modals::{DiscordModal, ModalParseError};
+use bevy_discord::serenity::all::ModalInteractionData;
+use serde_json::{Value, json};
+
+#[derive(DiscordModal, Debug, PartialEq)]
+#[modal(title = "Report a bug")]
+struct BugReport {
+    #[input(label = "Summary", placeholder = "What happened?", max_length = 100)]
+    summary: String,
+    #[input(custom_id = "repro", label = "Steps to reproduce", paragraph)]
+    steps: Option<String>,
+}
+
+fn inputs() -> Vec<Value> {
+    let modal = serde_json::to_value(BugReport::create_modal()).unwrap();
+
+    modal["components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["components"][0].clone())
+        .collect()
+}
+
+fn submission(values: &[(&str, &str)]) -> ModalInteractionData {
+    let rows: Vec<_> = values
+        .iter()
+        .map(|(custom_id, value)| {
+            json!({
+                "type": 1,
+                "components": [{ "type": 4, "custom_id": custom_id, "value": value }],
+            })
+        })
+        .collect();
+
+    serde_json::from_value(json!({ "custom_id": "bug_report", "components": rows })).unwrap()
+}
+
+#[test]
+fn modal_is_described_by_the_attributes() {
+    let modal = serde_json::to_value(BugReport::create_modal()).unwrap();
+
+    assert_eq!(BugReport::CUSTOM_ID, "bug_report");
+    assert_eq!(modal["custom_id"], "bug_report");
+    assert_eq!(modal["title"], "Report a bug");
+}
+
+#[test]
+fn fields_become_text_inputs() {
+    let inputs = inputs();
+
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[0]["custom_id"], "summary");
+    assert_eq!(inputs[0]["label"], "Summary");
+    assert_eq!(inputs[0]["placeholder"], "What happened?");
+    assert_eq!(inputs[0]["max_length"], 100);
+    assert_eq!(inputs[0]["required"], true);
+    assert_eq!(inputs[0]["style"], 1);
+
+    assert_eq!(inputs[1]["custom_id"], "repro");
+    assert_eq!(inputs[1]["required"], false);
+    assert_eq!(inputs[1]["style"], 2);
+}
+
+#[test]
+fn submissions_are_parsed() {
+    let data = submission(&[("summary", "It crashes"), ("repro", "")]);
+
+    assert_eq!(
+        BugReport::from_components(&data.components),
+        Ok(BugReport {
+            summary: "It crashes".to_string(),
+            steps: None,
+        })
+    );
+}
+
+#[test]
+fn missing_required_inputs_are_reported() {
+    let data = submission(&[("repro", "Click twice")]);
+
+    assert_eq!(
+        BugReport::from_components(&data.components),
+        Err(ModalParseError::MissingInput("summary".to_string()))
+    );
+}
@@ -0,0 +1,231 @@
+#![cfg(feature = "testing")]
+
+use bevy_app::{App, AppExit, Update};
+use bevy_discord::config::{BotMessageMode, DiscordBotConfig};
+use bevy_discord::messages::bot::{
+    BotDisconnectedMessage, DiscordEvent, DiscordEventKind, DiscordMessage,
+};
+use bevy_discord::messages::http::DiscordRequest;
+use bevy_discord::messages::payload::InteractionCreateMessagePayload;
+use bevy_discord::res::DiscordHttpBaseUrl;
+use bevy_discord::serenity::all::{
+    ChannelId, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage,
+    Interaction, MessageId,
+};
+use bevy_discord::slash_commands::{CommandInvoked, DiscordCommandAppExt};
+use bevy_discord::task::DiscordCommandsExt;
+use bevy_discord::testing::{
+    DiscordTestHarness, DiscordTestPlugin, FakeCommand, FakeMessage, FakeUser, MockDiscordServer,
+};
+use bevy_discord::{DiscordHttpPlugin, DiscordSystems};
+use bevy_ecs::prelude::*;
+use std::time::{Duration, Instant};
+
+fn pong(mut messages: MessageReader<DiscordMessage>, mut requests: MessageWriter<DiscordRequest>) {
+    for message in messages.read() {
+        if message.new_message.content == "ping" {
+            requests.write(DiscordRequest::SendMessage {
+                channel_id: message.new_message.channel_id,
+                message: CreateMessage::new().content("pong"),
+            });
+        }
+    }
+}
+
+fn app() -> (App, DiscordTestHarness) {
+    let mut app = App::new();
+    app.add_plugins(DiscordTestPlugin::default())
+        .add_systems(Update, pong.after(DiscordSystems));
+    let harness = app.world().resource::<DiscordTestHarness>().clone();
+    (app, harness)
+}
+
+#[test]
+fn requests_are_captured() {
+    let (mut app, harness) = app();
+
+    harness.send_message(FakeMessage::new("ping").channel(7).build());
+    app.update();
+
+    let requests = harness.take_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/api/v10/channels/7/messages");
+    assert_eq!(requests[0].json().unwrap()["content"], "pong");
+    assert_eq!(
+        harness.server().messages(ChannelId::new(7))[0].content,
+        "pong"
+    );
+    assert!(harness.requests().is_empty());
+}
+
+#[test]
+fn requests_only_wait_for_their_own_app() {
+    let (mut busy, _) = app();
+    busy.world_mut()
+        .commands()
+        .spawn_discord_task(|_| async { tokio::time::sleep(Duration::from_secs(30)).await });
+    busy.world_mut().flush();
+
+    let (mut app, harness) = app();
+    harness.send_message(FakeMessage::new("ping").channel(7).build());
+    app.update();
+
+    let started = Instant::now();
+    assert_eq!(harness.requests().len(), 1);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+fn answer_ping(app: &mut App) {
+    app.add_discord_command_observer(
+        "ping",
+        |invoked: On<CommandInvoked>, mut requests: MessageWriter<DiscordRequest>| {
+            requests.write(invoked.respond(CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content("pong"),
+            )));
+        },
+    );
+}
+
+/// Sends `/ping` in the channel `7`, and returns the messages it was answered with.
+fn use_ping(app: &mut App, harness: &DiscordTestHarness) -> Vec<String> {
+    let command = FakeCommand::new("ping")
+        .user(FakeUser::new(1).build())
+        .channel(7)
+        .build();
+    let token = command.token.clone();
+    harness.send_interaction(Interaction::Command(command));
+    app.update();
+
+    harness.requests();
+    harness
+        .server()
+        .interaction_messages(&token)
+        .into_iter()
+        .inspect(|message| assert_eq!(message.channel_id, ChannelId::new(7)))
+        .map(|message| message.content)
+        .collect()
+}
+
+#[test]
+fn commands_are_answered() {
+    let (mut app, harness) = app();
+    answer_ping(&mut app);
+
+    assert_eq!(use_ping(&mut app, &harness), ["pong"]);
+}
+
+#[test]
+fn commands_are_answered_with_payload_messages() {
+    let mut app = App::new();
+    app.add_plugins(
+        DiscordTestPlugin::default()
+            .config(DiscordBotConfig::default().message_mode(BotMessageMode::Payload)),
+    )
+    .init_resource::<Payloads>()
+    .add_systems(Update, count_payloads.after(DiscordSystems));
+    answer_ping(&mut app);
+    let harness = app.world().resource::<DiscordTestHarness>().clone();
+
+    assert_eq!(use_ping(&mut app, &harness), ["pong"]);
+    assert_eq!(app.world().resource::<Payloads>().0, 1);
+}
+
+#[derive(Resource, Default)]
+struct Payloads(usize);
+
+fn count_payloads(
+    mut payloads: MessageReader<InteractionCreateMessagePayload>,
+    mut count: ResMut<Payloads>,
+) {
+    count.0 += payloads.read().count();
+}
+
+#[test]
+fn http_plugin_uses_the_base_url() {
+    let server = MockDiscordServer::start().unwrap();
+    let mut app = App::new();
+    app.insert_resource(DiscordHttpBaseUrl(server.url().to_string()))
+        .add_plugins(DiscordHttpPlugin("token".to_string()));
+
+    app.world_mut().write_message(DiscordRequest::SendMessage {
+        channel_id: ChannelId::new(7),
+        message: CreateMessage::new().content("hello"),
+    });
+    app.update();
+
+    let started = Instant::now();
+    while server.messages(ChannelId::new(7)).is_empty() {
+        assert!(started.elapsed() < Duration::from_secs(5), "no request");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(server.messages(ChannelId::new(7))[0].content, "hello");
+}
+
+#[derive(Resource, Default)]
+struct Streamed {
+    messages: Vec<MessageId>,
+    events: Vec<DiscordEvent>,
+}
+
+fn stream(
+    mut messages: MessageReader<DiscordMessage>,
+    mut events: MessageReader<DiscordEvent>,
+    mut streamed: ResMut<Streamed>,
+) {
+    streamed
+        .messages
+        .extend(messages.read().map(|message| message.new_message.id));
+    streamed.events.extend(events.read().cloned());
+}
+
+#[test]
+fn concurrent_events_are_streamed_in_the_order_they_are_queued() {
+    let mut app = App::new();
+    app.add_plugins(
+        DiscordTestPlugin::default().config(DiscordBotConfig::default().event_stream(true)),
+    )
+    .init_resource::<Streamed>()
+    .add_systems(Update, stream.after(DiscordSystems));
+    let harness = app.world().resource::<DiscordTestHarness>().clone();
+
+    // Like serenity, which handles every event on its own task
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..50 {
+                    harness.send_message(FakeMessage::new("hi").build());
+                }
+            });
+        }
+    });
+    app.update();
+
+    let streamed = app.world().resource::<Streamed>();
+    assert_eq!(streamed.events.len(), 400);
+
+    for (index, event) in streamed.events.iter().enumerate() {
+        assert_eq!(event.sequence, index as u64 + 1);
+        let DiscordEventKind::DiscordMessage { new_message } = &event.kind else {
+            panic!("unexpected event {:?}", event.kind);
+        };
+        assert_eq!(new_message.id, streamed.messages[index]);
+    }
+    assert!(
+        streamed
+            .events
+            .windows(2)
+            .all(|events| events[0].received_at <= events[1].received_at)
+    );
+}
+
+#[test]
+fn exiting_writes_a_disconnected_message() {
+    let (mut app, _) = app();
+    app.update();
+
+    app.world_mut().write_message(AppExit::Success);
+    app.update();
+
+    let disconnected = app.world().resource::<Messages<BotDisconnectedMessage>>();
+    assert_eq!(disconnected.iter_current_update_messages().count(), 1);
+}